@@ -0,0 +1,146 @@
+/*
+concurrent ordered sets: LockCoupledList vs OptimisticList vs LazyList vs Mutex<BTreeSet>
+
+run with optimizations, debug numbers mean nothing:
+    cargo run --release --bin list_set_bench
+
+every set gets the same workload
+    prefilled with half the keys, then THREADS threads do random ops on random keys
+    two mixes: mostly reads, and an even split of contains/insert/remove
+lists are O(n) per op -> keep KEYS modest, that's the case these are meant for
+*/
+
+use lists::concurrent::{LazyList, LockCoupledList, OptimisticList};
+use std::collections::BTreeSet;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const KEYS: u32 = 256;
+const THREADS: u32 = 4;
+const OPS_PER_THREAD: u32 = 200_000;
+const ROUNDS: u32 = 3;
+
+trait Set: Default + Sync {
+    fn insert(&self, key: u32) -> bool;
+    fn remove(&self, key: u32) -> bool;
+    fn contains(&self, key: u32) -> bool;
+}
+
+impl Set for LockCoupledList<u32> {
+    fn insert(&self, key: u32) -> bool {
+        LockCoupledList::insert(self, key)
+    }
+    fn remove(&self, key: u32) -> bool {
+        LockCoupledList::remove(self, &key)
+    }
+    fn contains(&self, key: u32) -> bool {
+        LockCoupledList::contains(self, &key)
+    }
+}
+
+impl Set for OptimisticList<u32> {
+    fn insert(&self, key: u32) -> bool {
+        OptimisticList::insert(self, key)
+    }
+    fn remove(&self, key: u32) -> bool {
+        OptimisticList::remove(self, &key)
+    }
+    fn contains(&self, key: u32) -> bool {
+        OptimisticList::contains(self, &key)
+    }
+}
+
+impl Set for LazyList<u32> {
+    fn insert(&self, key: u32) -> bool {
+        LazyList::insert(self, key)
+    }
+    fn remove(&self, key: u32) -> bool {
+        LazyList::remove(self, &key)
+    }
+    fn contains(&self, key: u32) -> bool {
+        LazyList::contains(self, &key)
+    }
+}
+
+impl Set for Mutex<BTreeSet<u32>> {
+    fn insert(&self, key: u32) -> bool {
+        self.lock().unwrap().insert(key)
+    }
+    fn remove(&self, key: u32) -> bool {
+        self.lock().unwrap().remove(&key)
+    }
+    fn contains(&self, key: u32) -> bool {
+        self.lock().unwrap().contains(&key)
+    }
+}
+
+//xorshift -> cheap and the same sequence every run
+fn xorshift(seed: u32) -> impl FnMut() -> u32 {
+    let mut state = seed.max(1);
+    move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    }
+}
+
+//reads_percent of ops are contains, the rest split evenly between insert and remove
+fn run<S: Set>(reads_percent: u32) -> Duration {
+    let set = S::default();
+    for key in (0..KEYS).step_by(2) {
+        set.insert(key);
+    }
+
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let set = &set;
+            scope.spawn(move || {
+                let mut next = xorshift(t * 7919 + 1);
+                let mut hits = 0u32;
+                for _ in 0..OPS_PER_THREAD {
+                    let key = next() % KEYS;
+                    let roll = next() % 100;
+                    let hit = if roll < reads_percent {
+                        set.contains(key)
+                    } else if roll.is_multiple_of(2) {
+                        set.insert(key)
+                    } else {
+                        set.remove(key)
+                    };
+                    hits += hit as u32;
+                }
+                black_box(hits);
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn bench<S: Set>(name: &str) {
+    for (mix, reads_percent) in [("90% reads", 90), ("34% reads", 34)] {
+        let best = (0..ROUNDS).map(|_| run::<S>(reads_percent)).min().unwrap();
+        let ops = THREADS * OPS_PER_THREAD;
+        println!(
+            "{:<24} {:<10} {:>10.2?} {:>10.2} Mops/s",
+            name,
+            mix,
+            best,
+            ops as f64 / best.as_secs_f64() / 1e6,
+        );
+    }
+}
+
+fn main() {
+    println!(
+        "{} keys, {} threads x {} ops, best of {} rounds",
+        KEYS, THREADS, OPS_PER_THREAD, ROUNDS
+    );
+    bench::<LockCoupledList<u32>>("LockCoupledList");
+    bench::<OptimisticList<u32>>("OptimisticList");
+    bench::<LazyList<u32>>("LazyList");
+    bench::<Mutex<BTreeSet<u32>>>("Mutex<BTreeSet>");
+}
@@ -0,0 +1,121 @@
+/*
+push/pop throughput and allocation counts: unrolled::Stack vs second::List vs Vec
+
+run with optimizations, debug numbers mean nothing:
+    cargo run --release --bin unrolled_bench
+
+allocations counted by wrapping the system allocator
+    #[global_allocator] swaps out the allocator for the whole program
+    every alloc bumps a counter, then forwards to System
+*/
+
+use lists::{second, unrolled};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ELEMS: u64 = 1_000_000;
+const ROUNDS: u32 = 10;
+
+//every stack gets the same workload: fill, sum by iterating, drain
+trait Workload {
+    fn run(elems: u64) -> u64;
+}
+
+impl Workload for second::List<u64> {
+    fn run(elems: u64) -> u64 {
+        let mut stack = second::List::new();
+        for i in 0..elems {
+            stack.push(i);
+        }
+        let mut sum: u64 = stack.iter().sum();
+        while let Some(x) = stack.pop() {
+            sum = sum.wrapping_add(x);
+        }
+        sum
+    }
+}
+
+impl<const N: usize> Workload for unrolled::Stack<u64, N> {
+    fn run(elems: u64) -> u64 {
+        let mut stack = unrolled::Stack::<u64, N>::new();
+        for i in 0..elems {
+            stack.push(i);
+        }
+        let mut sum: u64 = stack.iter().sum();
+        while let Some(x) = stack.pop() {
+            sum = sum.wrapping_add(x);
+        }
+        sum
+    }
+}
+
+impl Workload for Vec<u64> {
+    fn run(elems: u64) -> u64 {
+        let mut stack = Vec::new();
+        for i in 0..elems {
+            stack.push(i);
+        }
+        let mut sum: u64 = stack.iter().sum();
+        while let Some(x) = stack.pop() {
+            sum = sum.wrapping_add(x);
+        }
+        sum
+    }
+}
+
+fn bench<W: Workload>(name: &str) {
+    //one untimed round to warm caches and the allocator
+    black_box(W::run(black_box(ELEMS)));
+
+    let allocs_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        black_box(W::run(black_box(ELEMS)));
+        best = best.min(start.elapsed());
+    }
+    let allocs = (ALLOCATIONS.load(Ordering::Relaxed) - allocs_before) / ROUNDS as usize;
+
+    let ops = 3 * ELEMS; // push + iterate + pop
+    println!(
+        "{:<24} {:>10.2?} {:>10.1} Mops/s {:>10} allocs",
+        name,
+        best,
+        ops as f64 / best.as_secs_f64() / 1e6,
+        allocs,
+    );
+}
+
+fn main() {
+    println!("{} elements, best of {} rounds", ELEMS, ROUNDS);
+    bench::<second::List<u64>>("second::List");
+    bench::<unrolled::Stack<u64, 8>>("unrolled::Stack<_, 8>");
+    bench::<unrolled::Stack<u64, 32>>("unrolled::Stack<_, 32>");
+    bench::<unrolled::Stack<u64, 128>>("unrolled::Stack<_, 128>");
+    bench::<Vec<u64>>("Vec");
+}
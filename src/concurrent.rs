@@ -0,0 +1,174 @@
+/*
+concurrent containers -> shared between threads through &self
+    no Mutex around the whole container, threads work on different parts at once
+
+lock-free ones (TreiberStack, MpscQueue, HarrisList)
+    every change is one atomic operation on a pointer (compare-and-swap, swap)
+    a thread that loses a compare-and-swap just retries with the new value
+lock-based list sets (LockCoupledList, OptimisticList, LazyList)
+    a Mutex in every node instead -> a change locks just the nodes around it
+    they differ in how much of the walk to the right spot happens under locks
+
+hard part is freeing nodes
+    a thread can unlink a node while another thread is still reading it
+    Rc/Arc can't help -> bumping a count on a node you only have a raw pointer to is the same race
+epoch.rs holds back frees until no thread can still be looking, for the containers that need it
+    (MpscQueue doesn't -> only its single consumer ever frees nodes)
+    (LockCoupledList doesn't either -> its nodes are Arc'd, every reader holds a count or a lock)
+*/
+
+pub(crate) mod epoch;
+mod harris;
+mod lazy;
+mod lock_coupled;
+#[cfg(test)]
+mod linearizability;
+mod mpsc;
+mod optimistic;
+mod treiber;
+
+pub use harris::HarrisList;
+pub use lazy::LazyList;
+pub use lock_coupled::LockCoupledList;
+pub use mpsc::{MpscQueue, PopResult};
+pub use optimistic::OptimisticList;
+pub use treiber::TreiberStack;
+
+/*
+the three ordered sets share one API -> one test suite, same as stack.rs's stack_tests!
+    set_tests!(module_name, Type) -> module of #[test]s run against Type
+*/
+#[cfg(test)]
+macro_rules! set_tests {
+    ($name:ident, $set:ident) => {
+        mod $name {
+            use crate::concurrent::$set;
+            use std::sync::Arc;
+            use std::thread;
+
+            #[cfg(not(miri))]
+            const OPS: usize = 20_000;
+            #[cfg(miri)]
+            const OPS: usize = 100;
+            const THREADS: usize = 4;
+            const KEYS: u32 = 64;
+
+            //same xorshift as sixth.rs's stress test, seeded per thread
+            fn xorshift(seed: u32) -> impl FnMut() -> u32 {
+                let mut state = seed.max(1);
+                move || {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state
+                }
+            }
+
+            #[test]
+            fn basics() {
+                let set = $set::new();
+                assert!(!set.contains(&1));
+                assert!(!set.remove(&1));
+
+                assert!(set.insert(2));
+                assert!(set.insert(1));
+                assert!(set.insert(3));
+                assert!(!set.insert(2));
+                assert!(set.contains(&1) && set.contains(&2) && set.contains(&3));
+                assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+                assert!(set.remove(&2));
+                assert!(!set.remove(&2));
+                assert!(!set.contains(&2));
+                assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 3]);
+            }
+
+            #[test]
+            fn matches_btreeset() {
+                use std::collections::BTreeSet;
+
+                let set = $set::new();
+                let mut model = BTreeSet::new();
+                let mut next = xorshift(7);
+                for _ in 0..OPS {
+                    let key = next() % KEYS;
+                    match next() % 3 {
+                        0 => assert_eq!(set.insert(key), model.insert(key)),
+                        1 => assert_eq!(set.remove(&key), model.remove(&key)),
+                        _ => assert_eq!(set.contains(&key), model.contains(&key)),
+                    }
+                }
+                assert!(set.iter().eq(model.into_iter()));
+            }
+
+            #[test]
+            fn drops_elements() {
+                let tracker = Arc::new(());
+                {
+                    let set = $set::new();
+                    for i in 0..10 {
+                        set.insert((i, tracker.clone()));
+                    }
+                    assert!(set.remove(&(3, tracker.clone())));
+                }
+                // Removed ones may wait for the epoch -> only check what was still linked
+                assert!(Arc::strong_count(&tracker) <= 2);
+            }
+
+            #[test]
+            fn long_list_drop() {
+                // Descending -> every insert lands at the front, stays O(1)
+                let set = $set::new();
+                for i in (0..if cfg!(miri) { 1_000 } else { 100_000 }).rev() {
+                    set.insert(i);
+                }
+            }
+
+            #[test]
+            fn concurrent_ops() {
+                // Per key: successful inserts - successful removes (over all threads) is 0 or 1,
+                // and it's 1 exactly when the key is in the set at the end
+                let set = Arc::new($set::new());
+                let handles: Vec<_> = (0..THREADS)
+                    .map(|t| {
+                        let set = set.clone();
+                        thread::spawn(move || {
+                            let mut balance = vec![0i64; KEYS as usize];
+                            let mut next = xorshift(t as u32 * 7919 + 1);
+                            for _ in 0..OPS {
+                                let key = next() % KEYS;
+                                match next() % 3 {
+                                    0 => balance[key as usize] += set.insert(key) as i64,
+                                    1 => balance[key as usize] -= set.remove(&key) as i64,
+                                    _ => {
+                                        set.contains(&key);
+                                    }
+                                }
+                            }
+                            balance
+                        })
+                    })
+                    .collect();
+
+                let mut balance = vec![0i64; KEYS as usize];
+                for handle in handles {
+                    for (total, mine) in balance.iter_mut().zip(handle.join().unwrap()) {
+                        *total += mine;
+                    }
+                }
+                for key in 0..KEYS {
+                    assert_eq!(balance[key as usize], set.contains(&key) as i64, "key {key}");
+                }
+                let elems: Vec<u32> = set.iter().collect();
+                assert!(elems.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+set_tests!(lock_coupled_list, LockCoupledList);
+#[cfg(test)]
+set_tests!(optimistic_list, OptimisticList);
+#[cfg(test)]
+set_tests!(lazy_list, LazyList);
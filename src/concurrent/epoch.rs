@@ -0,0 +1,365 @@
+/*
+epoch-based reclamation -> free unlinked nodes only once nobody can be reading them
+
+global epoch: a counter that only goes up
+every thread registers a participant, and pins it before touching shared nodes
+    pinned participant records the global epoch it saw
+    guard unpins on drop -> references from inside a pin must not outlive the guard
+unlinked nodes aren't freed straight away
+    they go into the thread's garbage bag, tagged with the global epoch at that moment
+
+global epoch only moves from e to e + 1 when every pinned participant is at e
+    a thread pinned when a node was unlinked is pinned at tag or earlier (tag - 1 at worst)
+    reaching tag + 1 needs it to be at tag, reaching tag + 2 needs it to be at tag + 1
+        -> it must have unpinned since, its references are gone
+so garbage tagged e is freed once the global epoch is e + 2
+
+bookkeeping kept simple
+    participants live in one Mutex'd Vec -> only locked to register, and to advance (try_lock)
+    each thread collects its own bag once it gets big enough
+    a thread exiting with garbage left moves it to a global orphan list, whoever collects next frees it
+
+thread-local can already be gone -> another thread-local's destructor using a container at thread exit
+    LOCAL.try_with instead of with, so that's not a panic (and an abort)
+    pin registers a one-off participant that lives as long as the guard
+    deferred garbage goes straight to the orphan list, collect only looks at the orphans
+*/
+
+use std::alloc::{self, Layout};
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+//bag gets collected once it holds this many nodes
+const COLLECT_EVERY: usize = 64;
+
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+static PARTICIPANTS: Mutex<Vec<Arc<Participant>>> = Mutex::new(Vec::new());
+static ORPHANS: Mutex<Vec<Garbage>> = Mutex::new(Vec::new());
+
+struct Participant {
+    //epoch << 1 | pinned
+    state: AtomicUsize,
+    active: AtomicBool,
+}
+
+//a type-erased Box<T> waiting to be freed
+struct Garbage {
+    ptr: *mut u8,
+    free: unsafe fn(*mut u8),
+    epoch: usize,
+}
+
+//may be freed by another thread (orphans) -> defer_destroy only takes T: Send, defer_dealloc runs no T code
+unsafe impl Send for Garbage {}
+
+unsafe fn free_box<T>(ptr: *mut u8) {
+    drop(unsafe { Box::from_raw(ptr as *mut T) });
+}
+
+//the allocation only -> whatever T held was already moved out
+unsafe fn dealloc_box<T>(ptr: *mut u8) {
+    unsafe { alloc::dealloc(ptr, Layout::new::<T>()) };
+}
+
+struct Local {
+    participant: Arc<Participant>,
+    guards: Cell<usize>,
+    bag: RefCell<Vec<Garbage>>,
+}
+
+impl Participant {
+    fn register() -> Arc<Self> {
+        let participant = Arc::new(Participant {
+            state: AtomicUsize::new(0),
+            active: AtomicBool::new(true),
+        });
+        PARTICIPANTS.lock().unwrap().push(participant.clone());
+        participant
+    }
+
+    fn pin(&self) {
+        let epoch = EPOCH.load(Ordering::Relaxed);
+        self.state.store(epoch << 1 | 1, Ordering::Relaxed);
+        //publish the pin before any shared pointer is read
+        fence(Ordering::SeqCst);
+    }
+
+    fn unregister(&self) {
+        self.state.store(0, Ordering::Release);
+        self.active.store(false, Ordering::Release);
+    }
+}
+
+impl Local {
+    fn register() -> Self {
+        Local {
+            participant: Participant::register(),
+            guards: Cell::new(0),
+            bag: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+//thread exit -> unregister, hand leftover garbage to whoever collects next
+impl Drop for Local {
+    fn drop(&mut self) {
+        self.participant.unregister();
+        let bag = mem::take(self.bag.get_mut());
+        if !bag.is_empty() {
+            ORPHANS.lock().unwrap().extend(bag);
+        }
+    }
+}
+
+thread_local! {
+    static LOCAL: Local = Local::register();
+}
+
+//raw pointer field -> Guard is neither Send nor Sync, it belongs to the thread that pinned
+pub(crate) struct Guard {
+    //pinned after LOCAL was destroyed -> the guard's own participant
+    teardown: Option<Arc<Participant>>,
+    _not_send: PhantomData<*mut ()>,
+}
+
+//nested pins are fine -> only the outermost one touches the participant
+pub(crate) fn pin() -> Guard {
+    let pinned = LOCAL.try_with(|local| {
+        let guards = local.guards.get();
+        local.guards.set(guards + 1);
+        if guards == 0 {
+            local.participant.pin();
+        }
+    });
+    let teardown = pinned.is_err().then(|| {
+        let participant = Participant::register();
+        participant.pin();
+        participant
+    });
+    Guard { teardown, _not_send: PhantomData }
+}
+
+impl Guard {
+    /*
+    free a Box<T> once every thread pinned right now has unpinned
+    safety: ptr came from Box::into_raw, is already unreachable for threads that pin later,
+        and is deferred once -> nobody else frees it
+    T is dropped by whichever thread collects it, at some later point
+        -> T: Send, and 'static so nothing it borrows can be gone by then
+    */
+    pub(crate) unsafe fn defer_destroy<T: Send + 'static>(&self, ptr: *mut T) {
+        self.defer(ptr as *mut u8, free_box::<T>);
+    }
+
+    /*
+    like defer_destroy, but only frees the memory -> the T in it is never dropped or even read
+        for nodes whose contents were already moved out (Treiber's ManuallyDrop elem)
+        no T code runs later, so no bounds on T
+    safety: as defer_destroy, and nothing in the T still needs dropping
+    */
+    pub(crate) unsafe fn defer_dealloc<T>(&self, ptr: *mut T) {
+        self.defer(ptr as *mut u8, dealloc_box::<T>);
+    }
+
+    fn defer(&self, ptr: *mut u8, free: unsafe fn(*mut u8)) {
+        let garbage = Garbage { ptr, free, epoch: EPOCH.load(Ordering::SeqCst) };
+        let mut garbage = Some(garbage);
+        let full = LOCAL.try_with(|local| {
+            let mut bag = local.bag.borrow_mut();
+            bag.extend(garbage.take());
+            bag.len() >= COLLECT_EVERY
+        });
+        match full {
+            Ok(true) => collect(),
+            Ok(false) => {}
+            //no bag left on this thread -> whoever collects next frees it
+            Err(_) => ORPHANS.lock().unwrap().extend(garbage),
+        }
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if let Some(participant) = &self.teardown {
+            participant.unregister();
+            return;
+        }
+        //LOCAL gone since the pin -> its drop already unpinned the participant
+        let _ = LOCAL.try_with(|local| {
+            let guards = local.guards.get() - 1;
+            local.guards.set(guards);
+            if guards == 0 {
+                local.participant.state.store(0, Ordering::Release);
+            }
+        });
+    }
+}
+
+//move the global epoch on if every pinned participant has caught up -> returns the epoch after
+fn try_advance() -> usize {
+    let epoch = EPOCH.load(Ordering::Relaxed);
+    fence(Ordering::SeqCst);
+
+    //someone else is registering or advancing -> let them, try again next time
+    let Ok(mut participants) = PARTICIPANTS.try_lock() else {
+        return epoch;
+    };
+    participants.retain(|p| p.active.load(Ordering::Acquire));
+    for participant in participants.iter() {
+        let state = participant.state.load(Ordering::Relaxed);
+        if state & 1 == 1 && state >> 1 != epoch {
+            return epoch;
+        }
+    }
+    fence(Ordering::Acquire);
+
+    match EPOCH.compare_exchange(epoch, epoch + 1, Ordering::Release, Ordering::Relaxed) {
+        Ok(_) => epoch + 1,
+        Err(current) => current,
+    }
+}
+
+/*
+free whatever in this thread's bag (and the orphan list) is two epochs old
+ready garbage is taken out first and freed after
+    freeing runs drop code, which may pin and defer again -> bag can't be borrowed then
+*/
+pub(crate) fn collect() {
+    let epoch = try_advance();
+    let is_ready = |garbage: &Garbage| garbage.epoch + 2 <= epoch;
+
+    let mut ready: Vec<Garbage> = LOCAL
+        .try_with(|local| {
+            let mut bag = local.bag.borrow_mut();
+            let (ready, waiting) = mem::take(&mut *bag).into_iter().partition(is_ready);
+            *bag = waiting;
+            ready
+        })
+        .unwrap_or_default();
+    if let Ok(mut orphans) = ORPHANS.try_lock() {
+        let (orphans_ready, waiting): (Vec<_>, _) =
+            mem::take(&mut *orphans).into_iter().partition(is_ready);
+        *orphans = waiting;
+        ready.extend(orphans_ready);
+    }
+
+    for garbage in ready {
+        unsafe { (garbage.free)(garbage.ptr) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{collect, pin};
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    struct CountDrops(Arc<AtomicUsize>);
+
+    impl Drop for CountDrops {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    //other tests may hold pins for a while -> keep collecting until it happens
+    fn collect_until(drops: &AtomicUsize, expected: usize) {
+        for _ in 0..100_000 {
+            if drops.load(Ordering::SeqCst) == expected {
+                return;
+            }
+            collect();
+            thread::yield_now();
+        }
+        panic!("garbage never collected");
+    }
+
+    #[test]
+    fn deferred_until_unpinned() {
+        let drops = Arc::new(AtomicUsize::new(0));
+
+        let reader = pin();
+        {
+            let guard = pin();
+            let garbage = Box::into_raw(Box::new(CountDrops(drops.clone())));
+            unsafe { guard.defer_destroy(garbage) };
+        }
+        // Still pinned on this thread -> epoch can't get two ahead of us
+        for _ in 0..10 {
+            collect();
+        }
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+        drop(reader);
+        collect_until(&drops, 1);
+    }
+
+    #[test]
+    fn orphans_freed_after_thread_exit() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let handle = {
+            let drops = drops.clone();
+            thread::spawn(move || {
+                let guard = pin();
+                for _ in 0..3 {
+                    let garbage = Box::into_raw(Box::new(CountDrops(drops.clone())));
+                    unsafe { guard.defer_destroy(garbage) };
+                }
+            })
+        };
+        handle.join().unwrap();
+        collect_until(&drops, 3);
+    }
+
+    #[test]
+    fn dealloc_leaves_contents_alone() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        {
+            let guard = pin();
+            let node = Box::into_raw(Box::new(CountDrops(drops.clone())));
+            // Moved out and dropped here -> freeing the node must not drop it again
+            drop(unsafe { std::ptr::read(node) });
+            unsafe { guard.defer_dealloc(node) };
+        }
+        for _ in 0..10 {
+            collect();
+        }
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn used_from_thread_local_destructor() {
+        //pins and defers from its destructor -> runs during thread teardown
+        struct Late(Arc<AtomicUsize>);
+
+        impl Drop for Late {
+            fn drop(&mut self) {
+                let guard = pin();
+                let garbage = Box::into_raw(Box::new(CountDrops(self.0.clone())));
+                unsafe { guard.defer_destroy(garbage) };
+                collect();
+            }
+        }
+
+        thread_local! {
+            static LATE: RefCell<Option<Late>> = const { RefCell::new(None) };
+        }
+
+        let drops = Arc::new(AtomicUsize::new(0));
+        let handle = {
+            let drops = drops.clone();
+            thread::spawn(move || {
+                // Destructors run in reverse order -> LATE, set up first, is dropped after LOCAL
+                LATE.with(|late| *late.borrow_mut() = Some(Late(drops)));
+                drop(pin());
+            })
+        };
+        handle.join().unwrap();
+        collect_until(&drops, 1);
+    }
+}
@@ -0,0 +1,381 @@
+/*
+Harris's lock-free sorted list (with Michael's fixes) -> an ordered map with no locks at all
+    LazyList marks a node, then unlinks it, both under locks
+    here both steps are compare-and-swaps, so they can be interleaved with anything
+
+the mark lives in the low bit of the node's own next pointer
+    nodes are pointer-aligned -> the low bit of a real node address is always 0
+    marking next (not a separate flag) is the trick:
+        after the mark, any CAS that expects the unmarked next fails
+        -> nobody can link a new node behind a node that's being removed
+remove: mark curr.next (the linearization point), then CAS pred.next from curr to curr's next
+    if that second CAS loses a race, the node stays linked but marked
+find unlinks marked nodes it walks past ("helping"), so removes always finish eventually
+    whoever's unlink CAS succeeds hands the node to epoch::defer_destroy -> exactly once
+
+get doesn't help -> one walk, no CAS, ignores marked nodes
+*/
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::epoch::{self, Guard};
+
+pub struct HarrisList<K, V> {
+    //never marked -> no node owns it
+    head: Link<K, V>,
+}
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    //low bit set -> this node is logically removed
+    next: Link<K, V>,
+}
+
+type Link<K, V> = AtomicPtr<Node<K, V>>;
+
+unsafe impl<K: Send + Sync, V: Send + Sync> Send for HarrisList<K, V> {}
+unsafe impl<K: Send + Sync, V: Send + Sync> Sync for HarrisList<K, V> {}
+
+//map_addr keeps the pointer's provenance -> Miri can still check every access
+fn marked<T>(ptr: *mut T) -> *mut T {
+    ptr.map_addr(|addr| addr | 1)
+}
+
+fn unmarked<T>(ptr: *mut T) -> *mut T {
+    ptr.map_addr(|addr| addr & !1)
+}
+
+fn is_marked<T>(ptr: *mut T) -> bool {
+    ptr.addr() & 1 == 1
+}
+
+impl<K: Ord, V> HarrisList<K, V> {
+    pub fn new() -> Self {
+        HarrisList { head: AtomicPtr::new(ptr::null_mut()) }
+    }
+
+    /*
+    the link to CAS and the first unmarked node >= key behind it (or null), and whether it's key
+    marked nodes on the way get unlinked
+        unlink CAS fails -> prev changed under us (or got marked itself), start again from head
+    unlinked nodes are dropped (key and value too) at some later collect, maybe on another thread
+        -> K and V: Send, and 'static so nothing they borrow can be gone by then
+        insert and remove go through here, so they carry the same bounds
+    */
+    fn find<'g>(&'g self, key: &K, guard: &'g Guard) -> (&'g Link<K, V>, *mut Node<K, V>, bool)
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        'retry: loop {
+            let mut prev = &self.head;
+            let mut curr = prev.load(Ordering::Acquire);
+            loop {
+                let Some(node) = (unsafe { curr.as_ref() }) else {
+                    return (prev, curr, false);
+                };
+                let next = node.next.load(Ordering::Acquire);
+                if is_marked(next) {
+                    let next = unmarked(next);
+                    let unlinked =
+                        prev.compare_exchange(curr, next, Ordering::AcqRel, Ordering::Acquire);
+                    if unlinked.is_err() {
+                        continue 'retry;
+                    }
+                    unsafe { guard.defer_destroy(curr) };
+                    curr = next;
+                    continue;
+                }
+                if node.key >= *key {
+                    return (prev, curr, node.key == *key);
+                }
+                prev = &node.next;
+                curr = next;
+            }
+        }
+    }
+
+    //false (and the pair is dropped) if key is already there
+    /// ```compile_fail
+    /// use lists::concurrent::HarrisList;
+    ///
+    /// let name = String::from("borrowed");
+    /// let map = HarrisList::new();
+    /// map.insert(1, &name);
+    /// ```
+    pub fn insert(&self, key: K, value: V) -> bool
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        let guard = epoch::pin();
+        let node = Box::into_raw(Box::new(Node {
+            key,
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }));
+        loop {
+            let (prev, curr, found) = self.find(unsafe { &(*node).key }, &guard);
+            if found {
+                //never shared -> free it right away
+                drop(unsafe { Box::from_raw(node) });
+                return false;
+            }
+            unsafe { (*node).next.store(curr, Ordering::Relaxed) };
+            //Release publishes the node, fails if prev moved on or was marked
+            if prev.compare_exchange(curr, node, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                return true;
+            }
+        }
+    }
+
+    //value is cloned -> other threads may still be reading the node
+    pub fn remove(&self, key: &K) -> Option<V>
+    where
+        K: Send + 'static,
+        V: Clone + Send + 'static,
+    {
+        let guard = epoch::pin();
+        loop {
+            let (prev, curr, found) = self.find(key, &guard);
+            if !found {
+                return None;
+            }
+            let node = unsafe { &*curr };
+            let next = node.next.load(Ordering::Acquire);
+            //already marked, or a node got linked behind it -> look again
+            if is_marked(next) {
+                continue;
+            }
+            let mark = node.next.compare_exchange(next, marked(next), Ordering::AcqRel, Ordering::Acquire);
+            if mark.is_err() {
+                continue;
+            }
+            //marked by us -> it's our remove, only the unlink is left
+            let value = node.value.clone();
+            if prev.compare_exchange(curr, next, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                unsafe { guard.defer_destroy(curr) };
+            } else {
+                //someone changed prev -> a find will unlink it on the way
+                self.find(key, &guard);
+            }
+            return Some(value);
+        }
+    }
+
+    /*
+    read-only walk for get/contains_key -> wait-free, never helps unlink
+    the node with key, if it's there and not marked
+    reference lives as long as the guard -> node can't be freed while it's used
+    */
+    fn find_unmarked<'g>(&'g self, key: &K, _guard: &'g Guard) -> Option<&'g Node<K, V>> {
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(node) = unsafe { unmarked(curr).as_ref() } {
+            let next = node.next.load(Ordering::Acquire);
+            if node.key >= *key {
+                return (node.key == *key && !is_marked(next)).then_some(node);
+            }
+            curr = next;
+        }
+        None
+    }
+
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        let guard = epoch::pin();
+        self.find_unmarked(key, &guard).map(|node| node.value.clone())
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        let guard = epoch::pin();
+        self.find_unmarked(key, &guard).is_some()
+    }
+
+    //snapshot of the unmarked pairs, in key order -> same guarantee as LazyList::iter
+    pub fn iter(&self) -> std::vec::IntoIter<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let _guard = epoch::pin();
+        let mut pairs = Vec::new();
+        let mut curr = self.head.load(Ordering::Acquire);
+        while let Some(node) = unsafe { unmarked(curr).as_ref() } {
+            let next = node.next.load(Ordering::Acquire);
+            if !is_marked(next) {
+                pairs.push((node.key.clone(), node.value.clone()));
+            }
+            curr = next;
+        }
+        pairs.into_iter()
+    }
+}
+
+impl<K: Ord, V> Default for HarrisList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//marked-but-still-linked nodes were never deferred -> they get freed here with the rest
+impl<K, V> Drop for HarrisList<K, V> {
+    fn drop(&mut self) {
+        let mut curr = unmarked(*self.head.get_mut());
+        while !curr.is_null() {
+            let mut node = unsafe { Box::from_raw(curr) };
+            curr = unmarked(*node.next.get_mut());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HarrisList;
+    use crate::concurrent::linearizability::{is_linearizable, Op, Recorder};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    #[cfg(not(miri))]
+    const OPS: usize = 2_000;
+    #[cfg(miri)]
+    const OPS: usize = 60;
+    const THREADS: usize = 4;
+
+    fn xorshift(seed: u32) -> impl FnMut() -> u32 {
+        let mut state = seed.max(1);
+        move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        }
+    }
+
+    #[test]
+    fn basics() {
+        let map = HarrisList::new();
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.remove(&1), None);
+
+        assert!(map.insert(2, "two"));
+        assert!(map.insert(1, "one"));
+        assert!(!map.insert(2, "zwei"));
+        assert_eq!(map.get(&2), Some("two"));
+        assert!(map.contains_key(&1));
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(1, "one"), (2, "two")]);
+
+        assert_eq!(map.remove(&1), Some("one"));
+        assert_eq!(map.remove(&1), None);
+        assert!(!map.contains_key(&1));
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(2, "two")]);
+    }
+
+    #[test]
+    fn marked_but_linked() {
+        // Mark by hand without unlinking -> invisible to readers, cleaned up by the next find
+        let map = HarrisList::new();
+        map.insert(1, 10);
+        map.insert(2, 20);
+        let first = unsafe { &*map.head.load(std::sync::atomic::Ordering::Acquire) };
+        let next = first.next.load(std::sync::atomic::Ordering::Acquire);
+        first.next.store(super::marked(next), std::sync::atomic::Ordering::Release);
+
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(2, 20)]);
+        // insert runs find from the head -> unlinks node 1 on the way
+        assert!(map.insert(1, 11));
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(1, 11), (2, 20)]);
+    }
+
+    #[test]
+    fn matches_btreemap() {
+        use std::collections::BTreeMap;
+
+        let map = HarrisList::new();
+        let mut model = BTreeMap::new();
+        let mut next = xorshift(11);
+        for _ in 0..OPS * 5 {
+            let key = next() % 32;
+            let value = next() % 100;
+            match next() % 3 {
+                0 => {
+                    let absent = !model.contains_key(&key);
+                    if absent {
+                        model.insert(key, value);
+                    }
+                    assert_eq!(map.insert(key, value), absent);
+                }
+                1 => assert_eq!(map.remove(&key), model.remove(&key)),
+                _ => assert_eq!(map.get(&key), model.get(&key).copied()),
+            }
+        }
+        assert!(map.iter().eq(model.into_iter()));
+    }
+
+    #[test]
+    fn drops_pairs() {
+        let tracker = Arc::new(());
+        {
+            let map = HarrisList::new();
+            for i in 0..10 {
+                map.insert(i, tracker.clone());
+            }
+            // Duplicate key -> its value is dropped straight away
+            assert!(!map.insert(0, tracker.clone()));
+            assert_eq!(Arc::strong_count(&tracker), 11);
+        }
+        assert_eq!(Arc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn long_list_drop() {
+        let map = HarrisList::new();
+        for i in (0..if cfg!(miri) { 1_000 } else { 100_000 }).rev() {
+            map.insert(i, ());
+        }
+    }
+
+    #[test]
+    fn linearizable() {
+        // Few keys -> lots of contention on each one
+        for round in 0..3u32 {
+            let map = Arc::new(HarrisList::new());
+            let recorder = Arc::new(Recorder::new());
+            //all threads start together -> otherwise the first may finish before the last spawns
+            let start = Arc::new(Barrier::new(THREADS));
+            let handles: Vec<_> = (0..THREADS as u32)
+                .map(|t| {
+                    let map = map.clone();
+                    let recorder = recorder.clone();
+                    let start = start.clone();
+                    thread::spawn(move || {
+                        start.wait();
+                        let mut next = xorshift(round * 101 + t * 7919 + 1);
+                        let mut events = Vec::new();
+                        for _ in 0..OPS {
+                            let key = next() % 4;
+                            let op = match next() % 3 {
+                                0 => Op::Insert(key, t * 1000 + next() % 1000),
+                                1 => Op::Remove(key),
+                                _ => Op::Get(key),
+                            };
+                            events.push(recorder.record(op, |op| match *op {
+                                Op::Insert(k, v) => map.insert(k, v).into(),
+                                Op::Remove(k) => map.remove(&k).into(),
+                                Op::Get(k) => map.get(&k).into(),
+                            }));
+                        }
+                        events
+                    })
+                })
+                .collect();
+
+            let history: Vec<_> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+            assert!(is_linearizable(&history), "round {round} is not linearizable");
+        }
+    }
+}
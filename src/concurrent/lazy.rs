@@ -0,0 +1,177 @@
+/*
+lazy list -> optimistic list, plus a mark on every node so validation doesn't re-walk
+    remove is two steps: mark the node (logically gone), then unlink it (physically gone)
+    both happen with pred and curr locked
+
+validate is now local: neither node marked, pred still points at curr
+    unmarked pred -> it's still in the list, no need to walk from the head to find out
+contains takes no locks at all
+    first node >= elem, is it elem and unmarked?
+    a node marked mid-walk counts as removed at the moment it was marked -> still linearizable
+
+nodes freed through the epoch, same as OptimisticList
+*/
+
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use super::epoch::{self, Guard};
+
+pub struct LazyList<T> {
+    //sentinel, elem None -> comes before every element, never marked
+    head: Box<Node<T>>,
+}
+
+struct Node<T> {
+    elem: Option<T>,
+    lock: Mutex<()>,
+    marked: AtomicBool,
+    next: AtomicPtr<Node<T>>,
+}
+
+unsafe impl<T: Send + Sync> Send for LazyList<T> {}
+unsafe impl<T: Send + Sync> Sync for LazyList<T> {}
+
+fn lock(mutex: &Mutex<()>) -> MutexGuard<'_, ()> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<T> Node<T> {
+    fn new(elem: Option<T>, next: *mut Node<T>) -> Self {
+        Node {
+            elem,
+            lock: Mutex::new(()),
+            marked: AtomicBool::new(false),
+            next: AtomicPtr::new(next),
+        }
+    }
+
+    fn is_marked(&self) -> bool {
+        self.marked.load(Ordering::Acquire)
+    }
+}
+
+impl<T: Ord> LazyList<T> {
+    pub fn new() -> Self {
+        LazyList { head: Box::new(Node::new(None, ptr::null_mut())) }
+    }
+
+    //same walk as OptimisticList::find, marked nodes are walked through like any other
+    fn find<'g>(&'g self, elem: &T, _guard: &'g Guard) -> (&'g Node<T>, *mut Node<T>) {
+        let mut pred: &Node<T> = &self.head;
+        let mut curr = pred.next.load(Ordering::Acquire);
+        while let Some(node) = unsafe { curr.as_ref() } {
+            if node.elem.as_ref() >= Some(elem) {
+                break;
+            }
+            pred = node;
+            curr = node.next.load(Ordering::Acquire);
+        }
+        (pred, curr)
+    }
+
+    fn validate(pred: &Node<T>, curr: *mut Node<T>) -> bool {
+        !pred.is_marked()
+            && unsafe { curr.as_ref() }.is_none_or(|node| !node.is_marked())
+            && pred.next.load(Ordering::Acquire) == curr
+    }
+
+    //wait-free -> one walk, no locks, no retries
+    pub fn contains(&self, elem: &T) -> bool {
+        let guard = epoch::pin();
+        let (_, curr) = self.find(elem, &guard);
+        unsafe { curr.as_ref() }
+            .is_some_and(|node| node.elem.as_ref() == Some(elem) && !node.is_marked())
+    }
+
+    pub fn insert(&self, elem: T) -> bool {
+        let guard = epoch::pin();
+        loop {
+            let (pred, curr) = self.find(&elem, &guard);
+            let curr_ref = unsafe { curr.as_ref() };
+            let _pred_lock = lock(&pred.lock);
+            let _curr_lock = curr_ref.map(|node| lock(&node.lock));
+            if !Self::validate(pred, curr) {
+                continue;
+            }
+            if curr_ref.is_some_and(|node| node.elem.as_ref() == Some(&elem)) {
+                return false;
+            }
+            let node = Box::into_raw(Box::new(Node::new(Some(elem), curr)));
+            pred.next.store(node, Ordering::Release);
+            return true;
+        }
+    }
+
+    /*
+    removed node (and its T) is only freed at some later collect, maybe on another thread
+        -> T: Send, and 'static so nothing it borrows can be gone by then
+    */
+    /// ```compile_fail
+    /// use lists::concurrent::LazyList;
+    ///
+    /// let name = String::from("borrowed");
+    /// let list = LazyList::new();
+    /// list.insert(&name);
+    /// list.remove(&&name);
+    /// ```
+    pub fn remove(&self, elem: &T) -> bool
+    where
+        T: Send + 'static,
+    {
+        let guard = epoch::pin();
+        loop {
+            let (pred, curr) = self.find(elem, &guard);
+            let Some(curr_ref) = (unsafe { curr.as_ref() }) else {
+                return false;
+            };
+            let _pred_lock = lock(&pred.lock);
+            let _curr_lock = lock(&curr_ref.lock);
+            if !Self::validate(pred, curr) {
+                continue;
+            }
+            if curr_ref.elem.as_ref() != Some(elem) {
+                return false;
+            }
+            //mark first -> contains sees it gone from here on, even before the unlink
+            curr_ref.marked.store(true, Ordering::Release);
+            pred.next.store(curr_ref.next.load(Ordering::Acquire), Ordering::Release);
+            unsafe { guard.defer_destroy(curr) };
+            return true;
+        }
+    }
+
+    //no locks, skips marked nodes -> each element was in the set at some point during the walk
+    pub fn iter(&self) -> std::vec::IntoIter<T>
+    where
+        T: Clone,
+    {
+        let _guard = epoch::pin();
+        let mut elems = Vec::new();
+        let mut curr = self.head.next.load(Ordering::Acquire);
+        while let Some(node) = unsafe { curr.as_ref() } {
+            if !node.is_marked() {
+                elems.extend(node.elem.clone());
+            }
+            curr = node.next.load(Ordering::Acquire);
+        }
+        elems.into_iter()
+    }
+}
+
+impl<T: Ord> Default for LazyList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for LazyList<T> {
+    fn drop(&mut self) {
+        let mut curr = *self.head.next.get_mut();
+        while !curr.is_null() {
+            let mut node = unsafe { Box::from_raw(curr) };
+            curr = *node.next.get_mut();
+        }
+    }
+}
@@ -0,0 +1,235 @@
+/*
+linearizability checker for concurrent maps -> test-only
+    linearizable: every op looks like it happened at one instant between its call and its return
+    so there must be a sequential order of the recorded ops that
+        respects real time (a returned before b was called -> a comes first)
+        gives every op the result it actually got, when replayed on a BTreeMap
+
+recording: a shared clock ticks before each call and after each return
+    clock is one SeqCst counter -> the ticks are a total order consistent with real time
+
+checking: Wing & Gong search
+    pick any op that could go next (no other pending op returned before it was called)
+    replay it on the model, keep going if the result matches, backtrack if not
+    remember (ops done, model state) pairs that already failed (Lowe's cache) -> no repeated work
+map ops on different keys never affect each other
+    -> check each key's history on its own, much smaller searches
+*/
+
+use std::collections::{BTreeMap, HashSet};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Op<K, V> {
+    Insert(K, V),
+    Remove(K),
+    Get(K),
+}
+
+//insert says whether it inserted, remove and get hand back a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Ret<V> {
+    Inserted(bool),
+    Value(Option<V>),
+}
+
+impl<V> From<bool> for Ret<V> {
+    fn from(inserted: bool) -> Self {
+        Ret::Inserted(inserted)
+    }
+}
+
+impl<V> From<Option<V>> for Ret<V> {
+    fn from(value: Option<V>) -> Self {
+        Ret::Value(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Event<K, V> {
+    call: u64,
+    ret: u64,
+    op: Op<K, V>,
+    result: Ret<V>,
+}
+
+pub(crate) struct Recorder {
+    clock: AtomicU64,
+}
+
+impl Recorder {
+    pub(crate) fn new() -> Self {
+        Recorder { clock: AtomicU64::new(0) }
+    }
+
+    //run f (the real operation) between two clock ticks
+    pub(crate) fn record<K, V, F>(&self, op: Op<K, V>, f: F) -> Event<K, V>
+    where
+        F: FnOnce(&Op<K, V>) -> Ret<V>,
+    {
+        let call = self.clock.fetch_add(1, Ordering::SeqCst);
+        let result = f(&op);
+        let ret = self.clock.fetch_add(1, Ordering::SeqCst);
+        Event { call, ret, op, result }
+    }
+}
+
+impl<K, V> Op<K, V> {
+    fn key(&self) -> &K {
+        match self {
+            Op::Insert(key, _) | Op::Remove(key) | Op::Get(key) => key,
+        }
+    }
+}
+
+//the sequential specification -> insert keeps an existing value, like the lists do
+fn apply<K: Ord + Clone, V: Clone>(model: &mut BTreeMap<K, V>, op: &Op<K, V>) -> Ret<V> {
+    match op {
+        Op::Insert(key, value) => {
+            if model.contains_key(key) {
+                Ret::Inserted(false)
+            } else {
+                model.insert(key.clone(), value.clone());
+                Ret::Inserted(true)
+            }
+        }
+        Op::Remove(key) => Ret::Value(model.remove(key)),
+        Op::Get(key) => Ret::Value(model.get(key).cloned()),
+    }
+}
+
+pub(crate) fn is_linearizable<K, V>(history: &[Event<K, V>]) -> bool
+where
+    K: Ord + Clone + Hash,
+    V: Clone + Eq + Hash,
+{
+    let mut by_key: BTreeMap<&K, Vec<&Event<K, V>>> = BTreeMap::new();
+    for event in history {
+        by_key.entry(event.op.key()).or_default().push(event);
+    }
+    by_key.into_values().all(|events| {
+        let mut search = Search { events, done: Vec::new(), failed: HashSet::new() };
+        search.done = vec![false; search.events.len()];
+        search.run(search.events.len(), BTreeMap::new())
+    })
+}
+
+struct Search<'a, K, V> {
+    events: Vec<&'a Event<K, V>>,
+    done: Vec<bool>,
+    failed: HashSet<(Vec<bool>, BTreeMap<K, V>)>,
+}
+
+impl<K, V> Search<'_, K, V>
+where
+    K: Ord + Clone + Hash,
+    V: Clone + Eq + Hash,
+{
+    //recursion depth is the number of ops on one key -> fine for test-sized histories
+    fn run(&mut self, left: usize, model: BTreeMap<K, V>) -> bool {
+        if left == 0 {
+            return true;
+        }
+        if self.failed.contains(&(self.done.clone(), model.clone())) {
+            return false;
+        }
+
+        //an op can go next only if it was called before every pending op returned
+        let first_return = (0..self.events.len())
+            .filter(|&i| !self.done[i])
+            .map(|i| self.events[i].ret)
+            .min()
+            .unwrap();
+
+        for i in 0..self.events.len() {
+            let event = self.events[i];
+            if self.done[i] || event.call > first_return {
+                continue;
+            }
+            let mut next = model.clone();
+            if apply(&mut next, &event.op) != event.result {
+                continue;
+            }
+            self.done[i] = true;
+            if self.run(left - 1, next) {
+                return true;
+            }
+            self.done[i] = false;
+        }
+
+        self.failed.insert((self.done.clone(), model));
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_linearizable, Event, Op, Ret};
+
+    fn event(call: u64, ret: u64, op: Op<u32, u32>, result: Ret<u32>) -> Event<u32, u32> {
+        Event { call, ret, op, result }
+    }
+
+    #[test]
+    fn sequential() {
+        let history = [
+            event(0, 1, Op::Insert(1, 10), Ret::Inserted(true)),
+            event(2, 3, Op::Get(1), Ret::Value(Some(10))),
+            event(4, 5, Op::Remove(1), Ret::Value(Some(10))),
+            event(6, 7, Op::Get(1), Ret::Value(None)),
+        ];
+        assert!(is_linearizable(&history));
+    }
+
+    #[test]
+    fn overlapping_ops_may_reorder() {
+        // Get overlaps the insert -> seeing the value, or not, are both fine
+        let saw_it = [
+            event(0, 3, Op::Insert(1, 10), Ret::Inserted(true)),
+            event(1, 2, Op::Get(1), Ret::Value(Some(10))),
+        ];
+        let missed_it = [
+            event(0, 3, Op::Insert(1, 10), Ret::Inserted(true)),
+            event(1, 2, Op::Get(1), Ret::Value(None)),
+        ];
+        assert!(is_linearizable(&saw_it));
+        assert!(is_linearizable(&missed_it));
+    }
+
+    #[test]
+    fn catches_violations() {
+        // Value seen before anyone started inserting it
+        let from_the_future = [
+            event(0, 1, Op::Get(1), Ret::Value(Some(10))),
+            event(2, 3, Op::Insert(1, 10), Ret::Inserted(true)),
+        ];
+        // Two removes can't both get the one value
+        let removed_twice = [
+            event(0, 1, Op::Insert(1, 10), Ret::Inserted(true)),
+            event(2, 5, Op::Remove(1), Ret::Value(Some(10))),
+            event(3, 4, Op::Remove(1), Ret::Value(Some(10))),
+        ];
+        // Stale read after a completed remove
+        let stale = [
+            event(0, 1, Op::Insert(1, 10), Ret::Inserted(true)),
+            event(2, 3, Op::Remove(1), Ret::Value(Some(10))),
+            event(4, 5, Op::Get(1), Ret::Value(Some(10))),
+        ];
+        assert!(!is_linearizable(&from_the_future));
+        assert!(!is_linearizable(&removed_twice));
+        assert!(!is_linearizable(&stale));
+    }
+
+    #[test]
+    fn keys_checked_separately() {
+        // Fine per key even though the interleaving across keys is arbitrary
+        let history = [
+            event(0, 5, Op::Insert(1, 10), Ret::Inserted(true)),
+            event(1, 2, Op::Insert(2, 20), Ret::Inserted(true)),
+            event(3, 4, Op::Get(2), Ret::Value(Some(20))),
+            event(6, 7, Op::Get(1), Ret::Value(Some(10))),
+        ];
+        assert!(is_linearizable(&history));
+    }
+}
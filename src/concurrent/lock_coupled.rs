@@ -0,0 +1,144 @@
+/*
+sorted linked list set with a lock in every node -> hand-over-hand (lock coupling)
+    one big Mutex around the list serializes everything
+    here a thread only ever holds the locks of two neighbouring nodes
+    so threads working in different parts of the list don't block each other
+
+walking: lock the next node before unlocking the current one
+    a node can only be unlinked by someone holding its predecessor's lock
+    -> whatever we hold a lock on can't be taken out from under us
+every thread locks in list order -> no deadlocks
+
+the lock guards a node's next link, nodes are Arc'd
+    a node's next (the Arc) moves around while another thread holds the node's lock
+    fifth.rs: moving a Box while someone has a reference into it is UB
+    moving an Arc is fine -> it never claims to be the only pointer
+*/
+
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+pub struct LockCoupledList<T> {
+    head: Mutex<Link<T>>,
+}
+
+type Link<T> = Option<Arc<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    next: Mutex<Link<T>>,
+}
+
+/*
+a locked link that keeps its node alive
+    MutexGuard borrows the Mutex -> normally can't move the Arc it lives in around with it
+    here the guard is stored next to the Arc that owns its Mutex, lifetime stretched to 'a
+    sound because:
+        the guard points into the Arc's heap allocation, which doesn't move when the Arc does
+        guard is declared first -> unlocked before the Arc is dropped
+        'a is the list borrow, the node can't outlive the walk anyway
+head's guard just borrows the list -> no Arc needed
+*/
+struct Locked<'a, T> {
+    guard: MutexGuard<'a, Link<T>>,
+    _node: Option<Arc<Node<T>>>,
+}
+
+//guards protect links, not invariants spanning several steps -> poisoning can be ignored
+fn lock<U>(mutex: &Mutex<U>) -> MutexGuard<'_, U> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<'a, T> Locked<'a, T> {
+    fn head(list: &'a LockCoupledList<T>) -> Self {
+        Locked { guard: lock(&list.head), _node: None }
+    }
+
+    fn node(node: Arc<Node<T>>) -> Self {
+        let guard = lock(&node.next);
+        let guard =
+            unsafe { mem::transmute::<MutexGuard<'_, Link<T>>, MutexGuard<'a, Link<T>>>(guard) };
+        Locked { guard, _node: Some(node) }
+    }
+}
+
+impl<T: Ord> LockCoupledList<T> {
+    pub fn new() -> Self {
+        LockCoupledList { head: Mutex::new(None) }
+    }
+
+    //locked link whose next node is the first one >= elem (or the end)
+    fn find(&self, elem: &T) -> Locked<'_, T> {
+        let mut pred = Locked::head(self);
+        loop {
+            let curr = match &*pred.guard {
+                Some(node) if node.elem < *elem => node.clone(),
+                _ => return pred,
+            };
+            //lock curr first, pred unlocks when it's overwritten
+            pred = Locked::node(curr);
+        }
+    }
+
+    pub fn contains(&self, elem: &T) -> bool {
+        let pred = self.find(elem);
+        pred.guard.as_ref().is_some_and(|node| node.elem == *elem)
+    }
+
+    //false if it was already there
+    pub fn insert(&self, elem: T) -> bool {
+        let mut pred = self.find(&elem);
+        if pred.guard.as_ref().is_some_and(|node| node.elem == elem) {
+            return false;
+        }
+        //pred locked -> its next can't be unlinked, splicing in front of it is safe
+        let next = pred.guard.take();
+        *pred.guard = Some(Arc::new(Node { elem, next: Mutex::new(next) }));
+        true
+    }
+
+    pub fn remove(&self, elem: &T) -> bool {
+        let mut pred = self.find(elem);
+        let curr = match &*pred.guard {
+            Some(node) if node.elem == *elem => node.clone(),
+            _ => return false,
+        };
+        //curr's next can only be read with curr locked too
+        let next = lock(&curr.next).take();
+        *pred.guard = next;
+        true
+    }
+
+    //snapshot, taken hand over hand -> consistent for each step, not for the whole list
+    pub fn iter(&self) -> std::vec::IntoIter<T>
+    where
+        T: Clone,
+    {
+        let mut elems = Vec::new();
+        let mut pred = Locked::head(self);
+        while let Some(node) = pred.guard.clone() {
+            elems.push(node.elem.clone());
+            pred = Locked::node(node);
+        }
+        elems.into_iter()
+    }
+}
+
+impl<T: Ord> Default for LockCoupledList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//&mut self -> no walk in progress, every Arc is unique, unwrap them one at a time
+impl<T> Drop for LockCoupledList<T> {
+    fn drop(&mut self) {
+        let mut link = self.head.get_mut().unwrap_or_else(PoisonError::into_inner).take();
+        while let Some(node) = link {
+            link = match Arc::try_unwrap(node) {
+                Ok(node) => node.next.into_inner().unwrap_or_else(PoisonError::into_inner),
+                Err(_) => None,
+            };
+        }
+    }
+}
@@ -0,0 +1,267 @@
+/*
+Vyukov's non-intrusive MPSC queue -> fifth::List with the tail made atomic
+    non-intrusive: the queue allocates its own nodes, elements don't carry a link field
+    fifth pushes at the tail and pops at the head, this one does the same
+    but any number of threads push, one thread pops
+
+push: swap the newest-node pointer to our node, then link the old newest node to it
+    one swap and one store, no retry loop -> wait-free
+pop: only the consumer ever moves the oldest end or frees a node
+    producers only touch the node they swapped out, and only to set its next
+    consumer can't get past that node until its next is set -> nothing to reclaim carefully
+
+stub node: the queue always holds at least one node, the oldest one is already consumed
+    so push never has to handle "queue was empty" (fifth's null tail check)
+
+between a producer's swap and its next store the queue is inconsistent
+    newest pointer has moved on, but the chain from the oldest end doesn't reach it yet
+    try_pop reports that as its own state, pop spins until the producer finishes
+*/
+
+use std::cell::UnsafeCell;
+use std::hint;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+use std::thread;
+
+pub struct MpscQueue<T> {
+    //newest node, producers swap here
+    head: AtomicPtr<Node<T>>,
+    //oldest node (already consumed), consumer only
+    tail: UnsafeCell<*mut Node<T>>,
+    consuming: AtomicBool,
+}
+
+struct Node<T> {
+    next: AtomicPtr<Node<T>>,
+    //None for the stub, and for any node whose value was popped
+    value: Option<T>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PopResult<T> {
+    Data(T),
+    Empty,
+    //a push is halfway done -> there is a value, it just can't be reached yet
+    Inconsistent,
+}
+
+//producers hand values to the consumer thread -> T: Send
+unsafe impl<T: Send> Send for MpscQueue<T> {}
+unsafe impl<T: Send> Sync for MpscQueue<T> {}
+
+impl<T> Node<T> {
+    fn new(value: Option<T>) -> *mut Self {
+        Box::into_raw(Box::new(Node { next: AtomicPtr::new(ptr::null_mut()), value }))
+    }
+}
+
+//marks the consumer side busy, cleared again on drop (even if the caller panics)
+struct Consumer<'a>(&'a AtomicBool);
+
+impl<'a> Consumer<'a> {
+    fn enter(flag: &'a AtomicBool) -> Self {
+        if flag.swap(true, Ordering::Acquire) {
+            panic!("MpscQueue popped from two threads at once");
+        }
+        Consumer(flag)
+    }
+}
+
+impl Drop for Consumer<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<T> MpscQueue<T> {
+    pub fn new() -> Self {
+        let stub = Node::new(None);
+        MpscQueue {
+            head: AtomicPtr::new(stub),
+            tail: UnsafeCell::new(stub),
+            consuming: AtomicBool::new(false),
+        }
+    }
+
+    //any thread, wait-free
+    pub fn push(&self, value: T) {
+        let node = Node::new(Some(value));
+        //AcqRel: Release publishes our node, Acquire gets prev's contents from its producer
+        let prev = self.head.swap(node, Ordering::AcqRel);
+        //prev can't be freed yet -> consumer stops at a node until its next is set
+        unsafe { (*prev).next.store(node, Ordering::Release) };
+    }
+
+    /*
+    consumer side -> popping from two threads at once panics
+        (checked with an atomic flag, it doesn't wait for the other thread)
+    popped node becomes the new stub, the old stub gets freed
+    */
+    pub fn try_pop(&self) -> PopResult<T> {
+        let _consumer = Consumer::enter(&self.consuming);
+        unsafe {
+            let tail = *self.tail.get();
+            let next = (*tail).next.load(Ordering::Acquire);
+            if !next.is_null() {
+                *self.tail.get() = next;
+                let value = (*next).value.take().unwrap();
+                drop(Box::from_raw(tail));
+                return PopResult::Data(value);
+            }
+            if self.head.load(Ordering::Acquire) == tail {
+                PopResult::Empty
+            } else {
+                PopResult::Inconsistent
+            }
+        }
+    }
+
+    //None only when the queue is really empty -> waits out a half-done push
+    pub fn pop(&self) -> Option<T> {
+        let mut spins = 0u32;
+        loop {
+            match self.try_pop() {
+                PopResult::Data(value) => return Some(value),
+                PopResult::Empty => return None,
+                //producer is between two instructions -> spin a bit, then let it run
+                PopResult::Inconsistent => {
+                    spins += 1;
+                    if spins < 64 {
+                        hint::spin_loop();
+                    } else {
+                        thread::yield_now();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for MpscQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//&mut self -> no producer is mid-push, the chain from tail reaches every node
+impl<T> Drop for MpscQueue<T> {
+    fn drop(&mut self) {
+        let mut cur = *self.tail.get_mut();
+        while !cur.is_null() {
+            let mut node = unsafe { Box::from_raw(cur) };
+            cur = *node.next.get_mut();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MpscQueue, Node, PopResult};
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[cfg(not(miri))]
+    const PER_THREAD: usize = 20_000;
+    #[cfg(miri)]
+    const PER_THREAD: usize = 50;
+    const THREADS: usize = 4;
+
+    #[test]
+    fn basics() {
+        let queue = MpscQueue::new();
+        assert_eq!(queue.try_pop(), PopResult::Empty);
+        assert_eq!(queue.pop(), None);
+
+        queue.push(1);
+        queue.push(2);
+        assert_eq!(queue.pop(), Some(1));
+        queue.push(3);
+        assert_eq!(queue.try_pop(), PopResult::Data(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn inconsistent() {
+        // Do the first half of a push by hand -> swapped in, not linked yet
+        let queue = MpscQueue::new();
+        queue.push(1);
+        let node = Node::new(Some(2));
+        let prev = queue.head.swap(node, Ordering::AcqRel);
+
+        assert_eq!(queue.try_pop(), PopResult::Data(1));
+        assert_eq!(queue.try_pop(), PopResult::Inconsistent);
+
+        // Second half -> value shows up
+        unsafe { (*prev).next.store(node, Ordering::Release) };
+        assert_eq!(queue.try_pop(), PopResult::Data(2));
+        assert_eq!(queue.try_pop(), PopResult::Empty);
+    }
+
+    #[test]
+    #[should_panic(expected = "two threads")]
+    fn second_consumer_panics() {
+        let queue: MpscQueue<i32> = MpscQueue::new();
+        // Pretend another thread is mid-pop
+        queue.consuming.store(true, Ordering::Relaxed);
+        queue.try_pop();
+    }
+
+    #[test]
+    fn drops_remaining() {
+        let tracker = Arc::new(());
+        {
+            let queue = MpscQueue::new();
+            for _ in 0..10 {
+                queue.push(tracker.clone());
+            }
+            drop(queue.pop());
+            assert_eq!(Arc::strong_count(&tracker), 10);
+        }
+        assert_eq!(Arc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn long_queue_drop() {
+        let queue = MpscQueue::new();
+        for i in 0..100_000 {
+            queue.push(i);
+        }
+    }
+
+    #[test]
+    fn many_producers() {
+        // Each producer's values come out once each, in the order it pushed them
+        let queue = Arc::new(MpscQueue::new());
+        let producers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_THREAD {
+                        queue.push((t, i));
+                    }
+                })
+            })
+            .collect();
+
+        let mut next = [0; THREADS];
+        let mut received = 0;
+        while received < THREADS * PER_THREAD {
+            match queue.pop() {
+                Some((t, i)) => {
+                    assert_eq!(i, next[t], "producer {t} out of order");
+                    next[t] += 1;
+                    received += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        for handle in producers {
+            handle.join().unwrap();
+        }
+        assert_eq!(queue.pop(), None);
+        assert!(next.iter().all(|&n| n == PER_THREAD));
+    }
+}
@@ -0,0 +1,191 @@
+/*
+optimistic list -> walk without locking, lock only the two nodes that matter, then check
+    lock coupling takes every lock on the way -> threads queue up behind each other at the front
+    here the walk is lock-free, only pred and curr get locked at the end
+
+but the walk saw the list without locks -> by the time we lock, pred may be gone
+validate: walk again from the head, pred must still be reachable and still point at curr
+    fails -> someone changed that spot meanwhile, start over
+    contains validates too, so it's linearizable, just like insert/remove
+
+nodes are read without a lock, so they can't just be freed on remove
+    removed nodes go to epoch::defer_destroy, every walk is pinned
+next is an AtomicPtr -> read while another thread (holding the locks) writes it
+lock is a separate Mutex<()> -> protects the node's next, but the pointer itself is atomic
+*/
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use super::epoch::{self, Guard};
+
+pub struct OptimisticList<T> {
+    //sentinel, elem None -> comes before every element, pred always exists
+    head: Box<Node<T>>,
+}
+
+struct Node<T> {
+    elem: Option<T>,
+    lock: Mutex<()>,
+    next: AtomicPtr<Node<T>>,
+}
+
+//readers share elements by reference, removed ones get dropped on other threads
+unsafe impl<T: Send + Sync> Send for OptimisticList<T> {}
+unsafe impl<T: Send + Sync> Sync for OptimisticList<T> {}
+
+fn lock(mutex: &Mutex<()>) -> MutexGuard<'_, ()> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<T> Node<T> {
+    fn new(elem: Option<T>, next: *mut Node<T>) -> Self {
+        Node { elem, lock: Mutex::new(()), next: AtomicPtr::new(next) }
+    }
+}
+
+impl<T: Ord> Node<T> {
+    //Option orders None first -> head sentinel is before everything
+    fn before(&self, elem: &T) -> bool {
+        self.elem.as_ref() < Some(elem)
+    }
+}
+
+impl<T: Ord> OptimisticList<T> {
+    pub fn new() -> Self {
+        OptimisticList { head: Box::new(Node::new(None, ptr::null_mut())) }
+    }
+
+    //last node before elem, and the one after it (first >= elem, or null)
+    //pinned -> nodes stay readable even if they're unlinked meanwhile
+    fn find<'g>(&'g self, elem: &T, _guard: &'g Guard) -> (&'g Node<T>, *mut Node<T>) {
+        let mut pred: &Node<T> = &self.head;
+        let mut curr = pred.next.load(Ordering::Acquire);
+        while let Some(node) = unsafe { curr.as_ref() } {
+            if !node.before(elem) {
+                break;
+            }
+            pred = node;
+            curr = node.next.load(Ordering::Acquire);
+        }
+        (pred, curr)
+    }
+
+    //pred still reachable from head, and still pointing at curr (both are locked)
+    fn validate(&self, pred: &Node<T>, curr: *mut Node<T>) -> bool {
+        let mut node: *const Node<T> = &*self.head;
+        while let Some(n) = unsafe { node.as_ref() } {
+            if ptr::eq(n, pred) {
+                return pred.next.load(Ordering::Acquire) == curr;
+            }
+            //walked past where pred would be -> it's been unlinked
+            if n.elem.as_ref() > pred.elem.as_ref() {
+                return false;
+            }
+            node = n.next.load(Ordering::Acquire);
+        }
+        false
+    }
+
+    pub fn contains(&self, elem: &T) -> bool {
+        let guard = epoch::pin();
+        loop {
+            let (pred, curr) = self.find(elem, &guard);
+            let curr_ref = unsafe { curr.as_ref() };
+            let _pred_lock = lock(&pred.lock);
+            let _curr_lock = curr_ref.map(|node| lock(&node.lock));
+            if self.validate(pred, curr) {
+                return curr_ref.is_some_and(|node| node.elem.as_ref() == Some(elem));
+            }
+        }
+    }
+
+    pub fn insert(&self, elem: T) -> bool {
+        let guard = epoch::pin();
+        loop {
+            let (pred, curr) = self.find(&elem, &guard);
+            let curr_ref = unsafe { curr.as_ref() };
+            let _pred_lock = lock(&pred.lock);
+            let _curr_lock = curr_ref.map(|node| lock(&node.lock));
+            if !self.validate(pred, curr) {
+                continue;
+            }
+            if curr_ref.is_some_and(|node| node.elem.as_ref() == Some(&elem)) {
+                return false;
+            }
+            let node = Box::into_raw(Box::new(Node::new(Some(elem), curr)));
+            pred.next.store(node, Ordering::Release);
+            return true;
+        }
+    }
+
+    /*
+    removed node (and its T) is only freed at some later collect, maybe on another thread
+        -> T: Send, and 'static so nothing it borrows can be gone by then
+    */
+    /// ```compile_fail
+    /// use lists::concurrent::OptimisticList;
+    ///
+    /// let name = String::from("borrowed");
+    /// let list = OptimisticList::new();
+    /// list.insert(&name);
+    /// list.remove(&&name);
+    /// ```
+    pub fn remove(&self, elem: &T) -> bool
+    where
+        T: Send + 'static,
+    {
+        let guard = epoch::pin();
+        loop {
+            let (pred, curr) = self.find(elem, &guard);
+            let Some(curr_ref) = (unsafe { curr.as_ref() }) else {
+                return false;
+            };
+            let _pred_lock = lock(&pred.lock);
+            let _curr_lock = lock(&curr_ref.lock);
+            if !self.validate(pred, curr) {
+                continue;
+            }
+            if curr_ref.elem.as_ref() != Some(elem) {
+                return false;
+            }
+            pred.next.store(curr_ref.next.load(Ordering::Acquire), Ordering::Release);
+            //walkers pinned right now may still be on it
+            unsafe { guard.defer_destroy(curr) };
+            return true;
+        }
+    }
+
+    //no locks -> each element was in the set at some point during the walk
+    pub fn iter(&self) -> std::vec::IntoIter<T>
+    where
+        T: Clone,
+    {
+        let _guard = epoch::pin();
+        let mut elems = Vec::new();
+        let mut curr = self.head.next.load(Ordering::Acquire);
+        while let Some(node) = unsafe { curr.as_ref() } {
+            elems.extend(node.elem.clone());
+            curr = node.next.load(Ordering::Acquire);
+        }
+        elems.into_iter()
+    }
+}
+
+impl<T: Ord> Default for OptimisticList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//&mut self -> nobody is walking, free what's still linked (removed nodes are the epoch's job)
+impl<T> Drop for OptimisticList<T> {
+    fn drop(&mut self) {
+        let mut curr = *self.head.next.get_mut();
+        while !curr.is_null() {
+            let mut node = unsafe { Box::from_raw(curr) };
+            curr = *node.next.get_mut();
+        }
+    }
+}
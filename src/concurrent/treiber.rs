@@ -0,0 +1,268 @@
+/*
+Treiber stack -> second::List's layout, with the head pointer made atomic
+    push: point the new node at the current head, CAS head to the new node
+    pop: read head's next, CAS head to next
+    CAS fails -> some other thread got in first, reload head and try again
+
+pop moves the element out of the node, but can't free the node yet
+    another thread may have loaded the same head and be about to read its next
+    so the node goes to epoch::defer_dealloc -> freed when no pinned thread can see it
+    that also rules out ABA: a node can't be freed and reused at the same address while
+        a thread that loaded it is still pinned
+
+elem is ManuallyDrop -> pop moves it out with ptr::read, freeing the node later must not drop it again
+    defer_dealloc only frees the memory, never touches elem -> pop needs no bounds on T
+*/
+
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::epoch;
+
+pub struct TreiberStack<T> {
+    head: AtomicPtr<Node<T>>,
+    _boo: PhantomData<T>,
+}
+
+struct Node<T> {
+    elem: ManuallyDrop<T>,
+    next: *mut Node<T>,
+}
+
+//elements move between threads through the stack -> T: Send is all it takes
+unsafe impl<T: Send> Send for TreiberStack<T> {}
+unsafe impl<T: Send> Sync for TreiberStack<T> {}
+
+impl<T> TreiberStack<T> {
+    pub fn new() -> Self {
+        TreiberStack { head: AtomicPtr::new(ptr::null_mut()), _boo: PhantomData }
+    }
+
+    //never reads a node someone else could free -> no pin needed
+    pub fn push(&self, elem: T) {
+        let node = Box::into_raw(Box::new(Node {
+            elem: ManuallyDrop::new(elem),
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            //node isn't shared yet -> plain write
+            unsafe { (*node).next = head };
+            //Release -> whoever Acquires the new head sees the node's contents
+            match self.head.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            if head.is_null() {
+                return None;
+            }
+            //pinned -> head can't have been freed, even if it's been popped meanwhile
+            let next = unsafe { (*head).next };
+            match self.head.compare_exchange_weak(head, next, Ordering::Acquire, Ordering::Acquire) {
+                Ok(_) => unsafe {
+                    //we unlinked it -> the element is ours, the node waits for the readers
+                    let elem = ptr::read(&*(*head).elem);
+                    guard.defer_dealloc(head);
+                    return Some(elem);
+                },
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /*
+    look at the top element without popping it
+    only for Copy elements:
+        pop moves the element out while a peeker may still be reading the node
+        for most types the moved-out value owns things (heap buffers...) -> popper could free them mid-read
+        a Copy type owns nothing and has no Drop -> the bytes in the node stay valid until the node is freed
+    and only for Sync elements:
+        the stack is Sync for T: Send, so every thread peeking gets a &T to the same node
+        a Copy + !Sync type (Cell<i32>) would be shared across threads through it
+    reference only lives inside f -> the pin lasts exactly as long as it's used
+    */
+    pub fn peek_with<R, F>(&self, f: F) -> R
+    where
+        T: Copy + Sync,
+        F: FnOnce(Option<&T>) -> R,
+    {
+        let _guard = epoch::pin();
+        let head = self.head.load(Ordering::Acquire);
+        f(unsafe { head.as_ref() }.map(|node| &*node.elem))
+    }
+
+    //a snapshot -> may be stale by the time the caller looks at it
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//&mut self -> no other thread can be in the stack, walk it like second::List's Drop
+    //popped nodes already in a garbage bag aren't reachable from head anymore
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        let mut cur = *self.head.get_mut();
+        while !cur.is_null() {
+            let mut node = unsafe { Box::from_raw(cur) };
+            cur = node.next;
+            unsafe { ManuallyDrop::drop(&mut node.elem) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TreiberStack;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    //Miri is slow, and spawning threads there is slower
+    #[cfg(not(miri))]
+    const PER_THREAD: usize = 20_000;
+    #[cfg(miri)]
+    const PER_THREAD: usize = 50;
+    const THREADS: usize = 4;
+
+    #[test]
+    fn basics() {
+        let stack = TreiberStack::new();
+        assert_eq!(stack.pop(), None);
+        assert!(stack.is_empty());
+
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert_eq!(stack.peek_with(|top| top.copied()), Some(3));
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+
+        stack.push(4);
+        assert_eq!(stack.pop(), Some(4));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+        assert!(stack.peek_with(|top| top.is_none()));
+    }
+
+    #[test]
+    fn drops_remaining() {
+        let tracker = Arc::new(());
+        {
+            let stack = TreiberStack::new();
+            for _ in 0..10 {
+                stack.push(tracker.clone());
+            }
+            drop(stack.pop());
+            assert_eq!(Arc::strong_count(&tracker), 10);
+        }
+        assert_eq!(Arc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn long_stack_drop() {
+        let stack = TreiberStack::new();
+        for i in 0..100_000 {
+            stack.push(i);
+        }
+    }
+
+    #[test]
+    fn concurrent_push_then_pop() {
+        // Every thread pushes its own range, then everyone pops at once
+        let stack = Arc::new(TreiberStack::new());
+        let pushers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = stack.clone();
+                thread::spawn(move || {
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                    }
+                })
+            })
+            .collect();
+        for handle in pushers {
+            handle.join().unwrap();
+        }
+
+        let poppers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let stack = stack.clone();
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    while let Some(elem) = stack.pop() {
+                        popped.push(elem);
+                    }
+                    popped
+                })
+            })
+            .collect();
+
+        let mut seen = vec![false; THREADS * PER_THREAD];
+        for handle in poppers {
+            for elem in handle.join().unwrap() {
+                assert!(!seen[elem], "{elem} popped twice");
+                seen[elem] = true;
+            }
+        }
+        assert!(seen.iter().all(|&popped| popped));
+    }
+
+    #[test]
+    fn concurrent_mixed() {
+        // Push and pop interleaved on every thread -> each element still comes out once
+        let stack = Arc::new(TreiberStack::new());
+        let popped = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let stack = stack.clone();
+                let popped = popped.clone();
+                thread::spawn(move || {
+                    let mut mine = Vec::new();
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                        if i % 2 == 1 {
+                            for _ in 0..2 {
+                                if let Some(elem) = stack.pop() {
+                                    mine.push(elem);
+                                }
+                            }
+                        }
+                        stack.peek_with(|top| assert!(top.is_none_or(|&e| e < THREADS * PER_THREAD)));
+                    }
+                    popped.fetch_add(mine.len(), Ordering::Relaxed);
+                    mine
+                })
+            })
+            .collect();
+
+        let mut seen = vec![false; THREADS * PER_THREAD];
+        for handle in handles {
+            for elem in handle.join().unwrap() {
+                assert!(!seen[elem], "{elem} popped twice");
+                seen[elem] = true;
+            }
+        }
+        let stack = Arc::try_unwrap(stack).ok().unwrap();
+        while let Some(elem) = stack.pop() {
+            assert!(!seen[elem], "{elem} popped twice");
+            seen[elem] = true;
+        }
+        assert!(seen.iter().all(|&popped| popped));
+        assert!(popped.load(Ordering::Relaxed) <= THREADS * PER_THREAD);
+    }
+}
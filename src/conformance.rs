@@ -0,0 +1,255 @@
+/*
+conformance suite -> same checks run over awkward element types
+    String: owns a heap allocation, must be moved out intact
+    Zst: zero-sized, no allocation at all -> easy to get counting wrong
+    Box<dyn Fn()>: fat pointer, unsized behind the box
+    DropCounter: records every drop -> catches leaks and double drops
+
+each element type says how to build element i and how to recognise it again
+pop/iter results are bound to explicitly typed locals
+    -> a list handing back the wrong type fails to compile
+
+first and second run through the Stack trait, third on its own
+*/
+
+use crate::stack::Stack;
+use crate::{first, second, third};
+use std::cell::{Cell, RefCell};
+
+const N: usize = 100;
+
+trait Subject: Sized {
+    fn make(i: usize) -> Self;
+    fn check(&self, i: usize);
+    //start of a test -> clear any per-thread bookkeeping
+    fn reset() {}
+    //end of a test -> every element made so far must have been dropped exactly once
+    fn all_dropped(_made: usize) {}
+}
+
+impl Subject for String {
+    fn make(i: usize) -> Self {
+        format!("elem-{}", i)
+    }
+
+    fn check(&self, i: usize) {
+        assert_eq!(*self, format!("elem-{}", i));
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Zst;
+
+impl Subject for Zst {
+    fn make(_i: usize) -> Self {
+        Zst
+    }
+
+    //nothing to tell Zsts apart -> the suite still checks how many come back
+    fn check(&self, _i: usize) {}
+}
+
+//tests run on their own threads -> thread locals keep them apart
+thread_local! {
+    static LAST_CALLED: Cell<Option<usize>> = const { Cell::new(None) };
+    static DROPS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+impl Subject for Box<dyn Fn()> {
+    fn make(i: usize) -> Self {
+        Box::new(move || LAST_CALLED.with(|last| last.set(Some(i))))
+    }
+
+    fn check(&self, i: usize) {
+        LAST_CALLED.with(|last| last.set(None));
+        self();
+        assert_eq!(LAST_CALLED.with(|last| last.get()), Some(i));
+    }
+}
+
+struct DropCounter(usize);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.borrow_mut().push(self.0));
+    }
+}
+
+impl Subject for DropCounter {
+    fn make(i: usize) -> Self {
+        DropCounter(i)
+    }
+
+    fn check(&self, i: usize) {
+        assert_eq!(self.0, i);
+    }
+
+    fn reset() {
+        DROPS.with(|drops| drops.borrow_mut().clear());
+    }
+
+    fn all_dropped(made: usize) {
+        let mut dropped = DROPS.with(|drops| drops.borrow().clone());
+        dropped.sort_unstable();
+        assert_eq!(dropped, (0..made).collect::<Vec<_>>(), "leaked or double-dropped elements");
+    }
+}
+
+fn stack_lifo<T: Subject, S: Stack<T> + Default>() {
+    T::reset();
+    let mut list = S::default();
+    for i in 0..N {
+        list.push(T::make(i));
+    }
+
+    let top: Option<&T> = list.peek();
+    top.unwrap().check(N - 1);
+
+    let mut count = 0;
+    for (elem, i) in list.iter().zip((0..N).rev()) {
+        let elem: &T = elem;
+        elem.check(i);
+        count += 1;
+    }
+    assert_eq!(count, N);
+
+    let top: Option<&mut T> = list.peek_mut();
+    top.unwrap().check(N - 1);
+
+    for i in (0..N).rev() {
+        let popped: Option<T> = list.pop();
+        popped.unwrap().check(i);
+    }
+    assert!(list.pop().is_none());
+
+    drop(list);
+    T::all_dropped(N);
+}
+
+fn stack_partial_then_drop<T: Subject, S: Stack<T> + Default>() {
+    T::reset();
+    let mut list = S::default();
+    for i in 0..N {
+        list.push(T::make(i));
+    }
+    for i in (N / 2..N).rev() {
+        list.pop().unwrap().check(i);
+    }
+    //other half still in the list -> dropped with it
+    drop(list);
+    T::all_dropped(N);
+}
+
+fn second_into_iter_then_drop<T: Subject>() {
+    T::reset();
+    let mut list = second::List::new();
+    for i in 0..N {
+        list.push(T::make(i));
+    }
+    for (elem, i) in list.iter_mut().zip((0..N).rev()) {
+        let elem: &mut T = elem;
+        elem.check(i);
+    }
+
+    let mut iter = list.into_iter();
+    let next: Option<T> = iter.next();
+    next.unwrap().check(N - 1);
+    //rest of the elements are still in the iterator -> dropped with it
+    drop(iter);
+    T::all_dropped(N);
+}
+
+fn third_shared_tails<T: Subject>() {
+    T::reset();
+    let mut list = third::List::new();
+    for i in 0..N {
+        list = list.prepend(T::make(i));
+    }
+
+    //two new lists sharing everything but their heads with list
+    let tail = list.tail();
+    let branch = tail.prepend(T::make(N));
+
+    let head: Option<&T> = list.head();
+    head.unwrap().check(N - 1);
+    branch.head().unwrap().check(N);
+    tail.head().unwrap().check(N - 2);
+
+    let mut count = 0;
+    for (elem, i) in branch.iter().skip(1).zip((0..N - 1).rev()) {
+        let elem: &T = elem;
+        elem.check(i);
+        count += 1;
+    }
+    assert_eq!(count, N - 1);
+
+    //nothing is freed until the last list pointing at a node goes away
+    drop(list);
+    drop(tail);
+    drop(branch);
+    T::all_dropped(N + 1);
+}
+
+macro_rules! conformance {
+    ($($name:ident: $elem:ty,)*) => {
+        $(
+            mod $name {
+                use super::*;
+
+                #[test]
+                fn first_lifo() {
+                    stack_lifo::<$elem, first::List<$elem>>();
+                }
+
+                #[test]
+                fn second_lifo() {
+                    stack_lifo::<$elem, second::List<$elem>>();
+                }
+
+                #[test]
+                fn first_partial_then_drop() {
+                    stack_partial_then_drop::<$elem, first::List<$elem>>();
+                }
+
+                #[test]
+                fn second_partial_then_drop() {
+                    stack_partial_then_drop::<$elem, second::List<$elem>>();
+                }
+
+                #[test]
+                fn second_into_iter_then_drop() {
+                    super::second_into_iter_then_drop::<$elem>();
+                }
+
+                #[test]
+                fn third_shared_tails() {
+                    super::third_shared_tails::<$elem>();
+                }
+            }
+        )*
+    };
+}
+
+conformance! {
+    string: String,
+    zst: Zst,
+    boxed_fn: Box<dyn Fn()>,
+    drop_counter: DropCounter,
+}
+
+#[test]
+fn zst_takes_no_space() {
+    //element is zero-sized -> nodes are just the next pointer
+    assert_eq!(std::mem::size_of::<Zst>(), 0);
+    let mut list = second::List::new();
+    list.push(Zst);
+    list.push(Zst);
+    assert_eq!(list.pop(), Some(Zst));
+    assert_eq!(list.pop(), Some(Zst));
+    assert_eq!(list.pop(), None);
+
+    let mut list = first::List::new();
+    list.push(Zst);
+    assert_eq!(list.pop(), Some(Zst));
+    assert_eq!(list.pop(), None);
+}
@@ -6,6 +6,7 @@ use std::mem;
     //same way mod was used to include first.rs in lib.rs
     //compile and run using 'cargo test'
 #[cfg(test)] //indicates to only compile 'test' when running tests
+#[allow(clippy::items_after_test_module)] //tests written first, list defined below them
 mod test {
     //made new module -> need to pull List explicitly to use it
     use super::List;
@@ -39,25 +40,62 @@ mod test {
         assert_eq!(list.pop(), Some(1));
         assert_eq!(list.pop(), None);
     }
+
+    #[test]
+    fn generic() {
+        let mut list = List::new();
+        list.push("one".to_string());
+        list.push("two".to_string());
+        assert_eq!(list.peek().map(String::as_str), Some("two"));
+        assert_eq!(list.pop(), Some("two".to_string()));
+        assert_eq!(list.pop(), Some("one".to_string()));
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn compact() {
+        use std::mem::size_of;
+        //null pointer optimization -> no tag, just the Box pointer
+        assert_eq!(size_of::<List<i32>>(), size_of::<usize>());
+        assert_eq!(size_of::<List<String>>(), size_of::<usize>());
+        assert_eq!(size_of::<List<()>>(), size_of::<usize>());
+    }
+
+    #[test]
+    fn long_list_drop() {
+        //hand-written Drop is iterative -> no stack overflow
+        let mut list = List::new();
+        for i in 0..100_000 {
+            list.push(i);
+        }
+    }
 }
 
 //pub allows use of List outside this module
-pub enum List {
-    head: Link,
+//generic over T -> List<T> holds any element type, not only i32
+pub struct List<T> {
+    head: Link<T>,
 }
 
-enum Link {
+enum Link<T> {
     Empty,
-    More(Box<Node>),
+    More(Box<Node<T>>),
 }
 //Box provides heap allocation -> ownership for allocation and drop contenets when out of scope
 
+/*
+null pointer optimization -> Box can never be null
+    so Empty is stored as a null pointer, More as the Box's pointer
+    no separate tag needed -> Link<T> (and List<T>) is exactly one pointer wide
+    same trick makes Option<Box<T>>, Option<&T> free
+*/
+
 //enum declares type containing one of several values
 // struct declraes type with many values simultaneously
 
-struct Node {
-    elem: i32,
-    next: List,
+struct Node<T> {
+    elem: T,
+    next: Link<T>,
 }
 
 
@@ -76,17 +114,17 @@ struct Node {
 
 //associate actual cod ewith type using impl
 //normal functions inside 'impl' are static
-impl List {
+impl<T> List<T> {
     pub fn new() -> Self {
-        List { head: None }
+        List { head: Link::Empty }
     }
 
     //Self is alias for type next to 'impl'
     //namespacing operator is :: -> use to refer to enum variants
-    pub fn push(&mut self, elem: i32){
+    pub fn push(&mut self, elem: T){
         //last expression of function implicitly returned
         let new_node = Box::new(Node {
-            elem: elem,
+            elem,
             //next: self.head, -> can't move out of borrow context
             next: mem::replace(&mut self.head, Link::Empty),
         });
@@ -101,7 +139,7 @@ impl List {
         //either Some<T> or None -> Option::None and Option::Some imported
     //could make own enum, but Option is ubiquitous and auto-impoted
     //chevrons indicate Option generic over T -> make Option of any type
-    pub fn pop(&mut self) -> Option<i32>{
+    pub fn pop(&mut self) -> Option<T>{
         //use pattern matching to see what Link it is
         //need head of the list by value -> not shared reference
             //since we have mutable reference to self, need to replace
@@ -116,13 +154,64 @@ impl List {
             //diverging function -> never return to caller
     }
 
+    //match on a reference -> bindings inside are references too, nothing moved
+    pub fn peek(&self) -> Option<&T> {
+        match &self.head {
+            Link::Empty => None,
+            Link::More(node) => Some(&node.elem),
+        }
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        match &mut self.head {
+            Link::Empty => None,
+            Link::More(node) => Some(&mut node.elem),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next: self.head.as_node() }
+    }
+
+}
+
+impl<T> Link<T> {
+    //Link is our own enum -> no as_deref, so write the Empty/More match once here
+    fn as_node(&self) -> Option<&Node<T>> {
+        match self {
+            Link::Empty => None,
+            Link::More(node) => Some(node),
+        }
+    }
+}
+
+//walks the nodes by shared reference, same shape as second.rs's Iter
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_node();
+            &node.elem
+        })
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 //trait is term in Rust for interfaces
     //if type implement type called 'Drop' -> rust uses destructor
     //when it goes out of scope -> will use to clean up
 //write iterative drop for List manually -> hoist nodes out of boxes
-impl Drop for List {
+impl<T> Drop for List<T> {
     fn drop(&mut self){
         let mut cur_link = mem::replace(&mut self.head, Link::Empty);
         // `while let` == "do this thing until this pattern doesn't match"
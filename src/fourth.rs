@@ -0,0 +1,310 @@
+/*
+doubly-linked deque -> every node points at next AND previous node
+    can push/pop from either end
+
+Box can't do this -> each node would need two owners
+Rc gives shared ownership, but only shared access to the insides
+    need interior mutability to rewire pointers through Rc
+    RefCell: borrow checking moved to runtime
+        borrow() -> Ref<T> (shared), borrow_mut() -> RefMut<T> (exclusive)
+        panics if rules broken, instead of compile error
+
+Rc in both directions builds cycles -> reference count never reaches zero
+    so only 'next' pointers own the node (Rc)
+    'prev' pointers are Weak -> don't keep node alive, must upgrade() to use
+    then dropping the list tears down the chain front to back, no leaks
+*/
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::{Rc, Weak};
+
+pub struct List<T> {
+    head: Link<T>,
+    //tail is owned too -> need a strong pointer to hand out Ref guards from peek_back
+    tail: Link<T>,
+}
+
+//same Link/Node layout as second.rs, just Rc<RefCell> instead of Box
+type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+type WeakLink<T> = Option<Weak<RefCell<Node<T>>>>;
+
+struct Node<T> {
+    elem: T,
+    next: Link<T>,
+    prev: WeakLink<T>,
+}
+
+impl<T> Node<T> {
+    fn new(elem: T) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Node {
+            elem,
+            next: None,
+            prev: None,
+        }))
+    }
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List { head: None, tail: None }
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        let new_head = Node::new(elem);
+        match self.head.take() {
+            Some(old_head) => {
+                //old head points back at new head (weakly), new head owns old head
+                old_head.borrow_mut().prev = Some(Rc::downgrade(&new_head));
+                new_head.borrow_mut().next = Some(old_head);
+                self.head = Some(new_head);
+            }
+            None => {
+                //empty list -> new node is both head and tail
+                self.tail = Some(new_head.clone());
+                self.head = Some(new_head);
+            }
+        }
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        let new_tail = Node::new(elem);
+        match self.tail.take() {
+            Some(old_tail) => {
+                new_tail.borrow_mut().prev = Some(Rc::downgrade(&old_tail));
+                old_tail.borrow_mut().next = Some(new_tail.clone());
+                self.tail = Some(new_tail);
+            }
+            None => {
+                self.head = Some(new_tail.clone());
+                self.tail = Some(new_tail);
+            }
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.take().map(|old_head| {
+            match old_head.borrow_mut().next.take() {
+                Some(new_head) => {
+                    new_head.borrow_mut().prev = None;
+                    self.head = Some(new_head);
+                }
+                None => {
+                    //last node -> drop the tail's strong pointer too
+                    self.tail = None;
+                }
+            }
+            //only one strong pointer should be left -> move elem out of Rc and RefCell
+            //ok() throws away the Err(Rc) case -> Rc<RefCell<Node>> isn't Debug
+            Rc::try_unwrap(old_head).ok().unwrap().into_inner().elem
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.take().map(|old_tail| {
+            //prev is Weak -> upgrade back to Rc, None once the front is gone
+            match old_tail.borrow_mut().prev.take().and_then(|prev| prev.upgrade()) {
+                Some(new_tail) => {
+                    //drops the strong pointer old tail's predecessor held
+                    new_tail.borrow_mut().next = None;
+                    self.tail = Some(new_tail);
+                }
+                None => {
+                    self.head = None;
+                }
+            }
+            Rc::try_unwrap(old_tail).ok().unwrap().into_inner().elem
+        })
+    }
+
+    /*
+    can't return &T from behind a RefCell -> borrow only valid while Ref alive
+        Ref::map projects the guard onto a field: Ref<Node<T>> -> Ref<T>
+    caller holds the guard, runtime borrow released when it's dropped
+    */
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|node| Ref::map(node.borrow(), |node| &node.elem))
+    }
+
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|node| Ref::map(node.borrow(), |node| &node.elem))
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.elem))
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.elem))
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//pop everything -> each pop unlinks one node, so no recursive drop
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+/*
+Iter and IterMut don't work here -> every element lives behind a RefCell
+    would have to hand out Ref guards that outlive the iterator's borrow of the node
+IntoIter is easy: same repeated pops as second.rs
+*/
+pub struct IntoIter<T>(List<T>);
+
+//implement the IntoIterator trait instead of an inherent into_iter -> for loops work too
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+}
+
+//DoubleEndedIterator: iterate from the back too, next_back() meets next() in the middle
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::List;
+
+    #[test]
+    fn basics() {
+        let mut list = List::new();
+
+        // Check empty list behaves right
+        assert_eq!(list.pop_front(), None);
+
+        // Populate list
+        list.push_front(1);
+        list.push_front(2);
+        list.push_front(3);
+
+        // Check normal removal
+        assert_eq!(list.pop_front(), Some(3));
+        assert_eq!(list.pop_front(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        list.push_front(4);
+        list.push_front(5);
+
+        // Check normal removal
+        assert_eq!(list.pop_front(), Some(5));
+        assert_eq!(list.pop_front(), Some(4));
+
+        // Check exhaustion
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), None);
+
+        // ---- back -----
+
+        // Check empty list behaves right
+        assert_eq!(list.pop_back(), None);
+
+        // Populate list
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        // Check normal removal
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_back(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        list.push_back(4);
+        list.push_back(5);
+
+        // Check normal removal
+        assert_eq!(list.pop_back(), Some(5));
+        assert_eq!(list.pop_back(), Some(4));
+
+        // Check exhaustion
+        assert_eq!(list.pop_back(), Some(1));
+        assert_eq!(list.pop_back(), None);
+    }
+
+    #[test]
+    fn peek() {
+        let mut list = List::new();
+        assert!(list.peek_front().is_none());
+        assert!(list.peek_back().is_none());
+        assert!(list.peek_front_mut().is_none());
+        assert!(list.peek_back_mut().is_none());
+
+        list.push_front(1); list.push_front(2); list.push_front(3);
+
+        assert_eq!(&*list.peek_front().unwrap(), &3);
+        assert_eq!(&mut *list.peek_front_mut().unwrap(), &mut 3);
+        assert_eq!(&*list.peek_back().unwrap(), &1);
+        assert_eq!(&mut *list.peek_back_mut().unwrap(), &mut 1);
+
+        *list.peek_back_mut().unwrap() = 42;
+        assert_eq!(list.pop_back(), Some(42));
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut list = List::new();
+        list.push_front(1); list.push_front(2); list.push_front(3);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next_back(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn no_leak() {
+        use std::rc::Rc;
+
+        //list holds clones of a shared Rc -> count goes back to 1 once list is gone
+        let tracker = Rc::new(());
+        {
+            let mut list = List::new();
+            for _ in 0..10 {
+                list.push_back(tracker.clone());
+                list.push_front(tracker.clone());
+            }
+            list.pop_back();
+            list.pop_front();
+            assert_eq!(Rc::strong_count(&tracker), 19);
+        }
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn long_list_drop() {
+        //iterative Drop -> no stack overflow on a long chain
+        let mut list = List::new();
+        for i in 0..100_000 {
+            list.push_back(i);
+        }
+    }
+}
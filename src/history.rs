@@ -0,0 +1,247 @@
+/*
+undo/redo history -> the thing third::List is most often used for
+    timeline: a third::List of states, head is the current one
+        commit = prepend, undo = tail -> every past state stays shared
+    redo: timelines we undid away from, most recent on top
+        undo pushes the timeline it leaves, redo pops it back
+        commit after undo starts a new future -> redo is cleared (like every editor)
+
+branch: a second History that starts out identical
+    every timeline is cloned by its head -> the two share all their states
+    redo and checkpoints are a plain Vec and HashMap though, copied entry by entry
+        O(redo + checkpoints): one head clone per timeline, one String per checkpoint name
+    after that they move independently
+
+checkpoint: a named timeline to come back to
+    restore jumps to it, the timeline it leaves goes on redo -> redo jumps back
+
+memory stats walk every timeline the history holds (current, redo, checkpoints)
+    a node is counted once however many timelines reach it
+    shared: reached from more than one timeline
+*/
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::third::List;
+
+pub struct History<S> {
+    timeline: List<S>,
+    redo: Vec<List<S>>,
+    checkpoints: HashMap<String, List<S>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub nodes: usize,
+    pub shared_nodes: usize,
+    pub bytes: usize,
+}
+
+impl<S> History<S> {
+    pub fn new(initial: S) -> Self {
+        History {
+            timeline: List::new().prepend(initial),
+            redo: Vec::new(),
+            checkpoints: HashMap::new(),
+        }
+    }
+
+    //timeline always has at least the initial state
+    pub fn current(&self) -> &S {
+        self.timeline.head().unwrap()
+    }
+
+    pub fn commit(&mut self, state: S) {
+        self.timeline = self.timeline.prepend(state);
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.timeline.len() > 1
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    //new current state, None if already at the oldest one
+    pub fn undo(&mut self) -> Option<&S> {
+        if !self.can_undo() {
+            return None;
+        }
+        let older = self.timeline.tail();
+        self.redo.push(std::mem::replace(&mut self.timeline, older));
+        Some(self.current())
+    }
+
+    pub fn redo(&mut self) -> Option<&S> {
+        self.timeline = self.redo.pop()?;
+        Some(self.current())
+    }
+
+    //states from the current one back to the initial one
+    pub fn past(&self) -> crate::third::Iter<'_, S> {
+        self.timeline.iter()
+    }
+
+    pub fn branch(&self) -> Self {
+        History {
+            timeline: self.timeline.clone(),
+            redo: self.redo.clone(),
+            checkpoints: self.checkpoints.clone(),
+        }
+    }
+
+    //same name again -> moves the checkpoint
+    pub fn checkpoint(&mut self, name: impl Into<String>) {
+        self.checkpoints.insert(name.into(), self.timeline.clone());
+    }
+
+    //None (and nothing changes) if there's no such checkpoint
+    pub fn restore(&mut self, name: &str) -> Option<&S> {
+        let target = self.checkpoints.get(name)?.clone();
+        self.redo.push(std::mem::replace(&mut self.timeline, target));
+        Some(self.current())
+    }
+
+    /*
+    walk each timeline until it reaches a node some earlier timeline already had
+        everything after that node was counted with it -> don't count again
+        but it's now reached twice, so mark it and its suffix shared
+            stop marking at a node already marked -> its suffix is marked too
+    every node is visited about twice at most -> O(nodes + timelines)
+    */
+    pub fn memory_stats(&self) -> MemoryStats {
+        let timelines = std::iter::once(&self.timeline)
+            .chain(&self.redo)
+            .chain(self.checkpoints.values());
+
+        //node address -> shared?
+        let mut seen: HashMap<usize, bool> = HashMap::new();
+        for timeline in timelines {
+            let mut addrs = timeline.node_addrs();
+            for addr in addrs.by_ref() {
+                match seen.entry(addr) {
+                    Entry::Occupied(mut shared) => {
+                        shared.insert(true);
+                        break;
+                    }
+                    Entry::Vacant(node) => {
+                        node.insert(false);
+                    }
+                }
+            }
+            for addr in addrs {
+                if seen.insert(addr, true) == Some(true) {
+                    break;
+                }
+            }
+        }
+
+        let nodes = seen.len();
+        MemoryStats {
+            nodes,
+            shared_nodes: seen.values().filter(|&&shared| shared).count(),
+            bytes: nodes * List::<S>::node_bytes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::History;
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::new(0);
+        assert_eq!(*history.current(), 0);
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), None);
+
+        history.commit(1);
+        history.commit(2);
+        assert_eq!(history.past().copied().collect::<Vec<_>>(), vec![2, 1, 0]);
+
+        assert_eq!(history.undo(), Some(&1));
+        assert_eq!(history.undo(), Some(&0));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.redo(), Some(&1));
+        assert_eq!(history.redo(), Some(&2));
+        assert_eq!(history.redo(), None);
+
+        // New commit after undo drops the old future
+        history.undo();
+        history.commit(20);
+        assert!(!history.can_redo());
+        assert_eq!(history.past().copied().collect::<Vec<_>>(), vec![20, 1, 0]);
+    }
+
+    #[test]
+    fn branch() {
+        let mut main = History::new("start");
+        main.commit("a");
+
+        let mut other = main.branch();
+        other.commit("b");
+        main.undo();
+
+        assert_eq!(*main.current(), "start");
+        assert_eq!(*other.current(), "b");
+        assert_eq!(other.undo(), Some(&"a"));
+        assert_eq!(main.redo(), Some(&"a"));
+    }
+
+    #[test]
+    fn checkpoints() {
+        let mut history = History::new(0);
+        history.commit(1);
+        history.checkpoint("one");
+        history.commit(2);
+        history.commit(3);
+
+        assert_eq!(history.restore("missing"), None);
+        assert_eq!(*history.current(), 3);
+
+        assert_eq!(history.restore("one"), Some(&1));
+        assert_eq!(history.undo(), Some(&0));
+        assert_eq!(history.redo(), Some(&1));
+        // Redo once more -> back to before the restore
+        assert_eq!(history.redo(), Some(&3));
+
+        history.checkpoint("one");
+        history.commit(4);
+        assert_eq!(history.restore("one"), Some(&3));
+    }
+
+    #[test]
+    fn memory_stats() {
+        let node = crate::third::List::<u64>::node_bytes();
+
+        let mut history = History::new(0u64);
+        for i in 1..10 {
+            history.commit(i);
+        }
+        let stats = history.memory_stats();
+        assert_eq!((stats.nodes, stats.shared_nodes, stats.bytes), (10, 0, 10 * node));
+
+        // Undoing 3 keeps the same 10 nodes alive
+        // redo holds the timelines from 9, 8 and 7 down -> only state 9 is reached once
+        for _ in 0..3 {
+            history.undo();
+        }
+        let stats = history.memory_stats();
+        assert_eq!((stats.nodes, stats.shared_nodes), (10, 9));
+
+        // New commit drops redo -> the 3 undone states go, the new one comes in
+        history.commit(100);
+        let stats = history.memory_stats();
+        assert_eq!((stats.nodes, stats.shared_nodes), (8, 0));
+
+        // Checkpoint shares the whole timeline
+        history.checkpoint("here");
+        history.commit(101);
+        let stats = history.memory_stats();
+        assert_eq!((stats.nodes, stats.shared_nodes), (9, 8));
+    }
+}
@@ -1,6 +1,19 @@
 pub mod first;
 pub mod second;
 pub mod third;
+pub mod fourth;
+pub mod fifth;
+pub mod sixth;
+pub mod stack;
+pub mod unrolled;
+pub mod persistent;
+pub mod stream;
+pub mod history;
+pub mod concurrent;
+pub mod sync;
+
+#[cfg(test)]
+mod conformance;
 
 #[cfg(test)]
 mod tests {
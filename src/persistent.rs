@@ -0,0 +1,77 @@
+/*
+persistent containers built out of third::List
+    third::List nodes are immutable and shared -> any structure made of them is too
+    every operation hands back a new version, old versions stay valid
+    new and old versions share whatever list nodes they have in common
+
+submodules hold one container each, the main types are re-exported here
+*/
+
+pub mod assoc_map;
+pub mod deque;
+pub mod queue;
+pub mod ralist;
+mod suspension;
+pub mod versioned;
+
+pub use assoc_map::{AssocMap, Change};
+pub use deque::Deque;
+pub use queue::Queue;
+pub use ralist::{GenericRaList, RaList};
+pub use versioned::Versioned;
+
+use crate::third;
+
+/*
+queue and deque both keep their elements as front ++ reverse(rear)
+    front: first element at the head, rear: last element at the head
+so both iterate the same way -> front in order, then rear backwards
+    rear is singly linked, can't walk it backwards -> collect its refs first (O(rear))
+*/
+pub struct Iter<'a, T> {
+    front: third::Iter<'a, T>,
+    rear: std::iter::Rev<std::vec::IntoIter<&'a T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn new(front: &'a third::List<T>, rear: &'a third::List<T>) -> Self {
+        Iter {
+            front: front.iter(),
+            rear: rear.iter().collect::<Vec<_>>().into_iter().rev(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.rear.next())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    //element that counts how often it gets cloned -> shows when a rebalance copies elements
+    #[derive(Debug)]
+    pub(super) struct Counted {
+        pub(super) value: i32,
+        clones: Rc<Cell<usize>>,
+    }
+
+    impl Counted {
+        pub(super) fn new(value: i32, clones: &Rc<Cell<usize>>) -> Self {
+            Counted { value, clones: clones.clone() }
+        }
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.clones.set(self.clones.get() + 1);
+            Counted::new(self.value, &self.clones)
+        }
+    }
+}
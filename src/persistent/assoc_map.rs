@@ -0,0 +1,270 @@
+/*
+association list -> a map that's just a third::List of (key, value) pairs
+    O(n) lookups, but for small maps that's fine, and versions are nearly free
+
+insert prepends -> newer pair shadows any older pair with the same key
+    nothing gets copied, old version still sees the old value
+get walks from the front -> first pair with the key is the live one
+remove drops every pair with the key (via third::List::filter)
+    everything after the last dropped pair stays shared with the old version
+    so a key never hides behind a removed pair -> no tombstones needed
+
+shadowed pairs pile up with repeated inserts -> compact() keeps only the live ones
+*/
+
+use std::borrow::Borrow;
+
+use crate::third::{self, List};
+
+pub struct AssocMap<K, V> {
+    entries: List<(K, V)>,
+}
+
+//one key's difference between two maps, see AssocMap::diff
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change<'a, K, V> {
+    Added(&'a K, &'a V),
+    Removed(&'a K, &'a V),
+    Changed(&'a K, &'a V, &'a V),
+}
+
+impl<K, V> AssocMap<K, V> {
+    pub fn new() -> Self {
+        AssocMap { entries: List::new() }
+    }
+
+    //remove takes every pair for a key -> any pair left means a live key
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    //live keys only -> O(n * live), see Iter
+    pub fn len(&self) -> usize
+    where
+        K: PartialEq,
+    {
+        self.iter().count()
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Self {
+        AssocMap { entries: self.entries.prepend((key, value)) }
+    }
+
+    //Borrow like HashMap::get -> AssocMap<String, _> can be queried with a &str
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.entries.iter().find(|(k, _)| k.borrow() == key).map(|(_, v)| v)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { entries: self.entries.iter(), seen: Vec::new() }
+    }
+}
+
+impl<K: Clone, V: Clone> AssocMap<K, V> {
+    //key not there -> filter hands back the same list, nothing copied
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: PartialEq + ?Sized,
+    {
+        AssocMap { entries: self.entries.filter(|(k, _)| k.borrow() != key) }
+    }
+
+    //drop shadowed pairs, live ones keep their order
+        //shares everything after the last shadowed pair, like remove
+    pub fn compact(&self) -> Self
+    where
+        K: PartialEq,
+    {
+        let mut seen: Vec<K> = Vec::new();
+        let entries = self.entries.filter(|(k, _)| {
+            if seen.contains(k) {
+                false
+            } else {
+                seen.push(k.clone());
+                true
+            }
+        });
+        AssocMap { entries }
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> AssocMap<K, V> {
+    /*
+    what changed going from self to newer?
+    pairs in their shared suffix are the same nodes -> can't differ
+        (and remove never leaves a key behind in a shared part)
+    so only keys that show up in the unshared prefixes can have changed
+        each one gets a full get() on both sides -> shadowing is handled
+    cost is O(prefix * n) -> cheap when the versions are close
+    */
+    pub fn diff<'a>(&'a self, newer: &'a Self) -> Vec<Change<'a, K, V>> {
+        let shared = self.entries.common_suffix_len(&newer.entries);
+        let old_prefix = self.entries.iter().take(self.entries.len() - shared);
+        let new_prefix = newer.entries.iter().take(newer.entries.len() - shared);
+
+        let mut keys: Vec<&K> = Vec::new();
+        for (k, _) in old_prefix.chain(new_prefix) {
+            if !keys.contains(&k) {
+                keys.push(k);
+            }
+        }
+
+        keys.into_iter()
+            .filter_map(|k| match (self.get(k), newer.get(k)) {
+                (Some(old), Some(new)) if old != new => Some(Change::Changed(k, old, new)),
+                (Some(old), None) => Some(Change::Removed(k, old)),
+                (None, Some(new)) => Some(Change::Added(k, new)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+//O(1), shares every pair
+impl<K, V> Clone for AssocMap<K, V> {
+    fn clone(&self) -> Self {
+        AssocMap { entries: self.entries.clone() }
+    }
+}
+
+impl<K, V> Default for AssocMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//inserted in iteration order -> later pairs shadow earlier ones, like HashMap
+impl<K, V> FromIterator<(K, V)> for AssocMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter().fold(AssocMap::new(), |map, (k, v)| map.insert(k, v))
+    }
+}
+
+//live pairs, newest first -> skips any key already yielded (O(live) check per pair)
+pub struct Iter<'a, K, V> {
+    entries: third::Iter<'a, (K, V)>,
+    seen: Vec<&'a K>,
+}
+
+impl<'a, K: PartialEq, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (k, v) in self.entries.by_ref() {
+            if !self.seen.contains(&k) {
+                self.seen.push(k);
+                return Some((k, v));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AssocMap, Change};
+
+    fn sorted(map: &AssocMap<&'static str, i32>) -> Vec<(&'static str, i32)> {
+        let mut pairs: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn basics() {
+        let map = AssocMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.get("a"), None);
+
+        let map = map.insert("a", 1).insert("b", 2);
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.get("b"), Some(&2));
+        assert_eq!(map.len(), 2);
+
+        // Shadowing -> old version keeps its value
+        let newer = map.insert("a", 10);
+        assert_eq!(newer.get("a"), Some(&10));
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(newer.len(), 2);
+        assert_eq!(sorted(&newer), vec![("a", 10), ("b", 2)]);
+    }
+
+    #[test]
+    fn remove() {
+        let map: AssocMap<_, _> = [("a", 1), ("b", 2), ("a", 3)].into_iter().collect();
+        let removed = map.remove("a");
+
+        // Shadowed pair goes too -> older value doesn't resurface
+        assert_eq!(removed.get("a"), None);
+        assert!(!removed.contains_key("a"));
+        assert_eq!(sorted(&removed), vec![("b", 2)]);
+        assert_eq!(map.get("a"), Some(&3));
+
+        // Missing key -> same list
+        let same = map.remove("zzz");
+        assert_eq!(same.entries.common_suffix_len(&map.entries), 3);
+
+        assert!(removed.remove("b").is_empty());
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let map = AssocMap::new().insert("flag".to_string(), true);
+        assert_eq!(map.get("flag"), Some(&true));
+        assert!(map.remove("flag").is_empty());
+    }
+
+    #[test]
+    fn compact() {
+        let base: AssocMap<_, _> = [("x", 0), ("y", 0)].into_iter().collect();
+        let mut map = base.clone();
+        for i in 1..=5 {
+            map = map.insert("a", i).insert("b", -i);
+        }
+        assert_eq!(map.entries.len(), 12);
+
+        let compacted = map.compact();
+        assert_eq!(compacted.entries.len(), 4);
+        assert_eq!(sorted(&compacted), sorted(&map));
+        // Nothing shadowed in the base -> it's shared, not copied
+        assert_eq!(compacted.entries.common_suffix_len(&base.entries), 2);
+    }
+
+    #[test]
+    fn diff() {
+        let base: AssocMap<_, _> = (0..50).map(|i| (i, i)).collect();
+        let newer = base.insert(3, 30).insert(100, 100).remove(&7);
+
+        let mut changes = base.diff(&newer);
+        changes.sort_by_key(|change| match change {
+            Change::Added(k, _) | Change::Removed(k, _) | Change::Changed(k, _, _) => **k,
+        });
+        assert_eq!(
+            changes,
+            vec![
+                Change::Changed(&3, &3, &30),
+                Change::Removed(&7, &7),
+                Change::Added(&100, &100),
+            ]
+        );
+        assert!(base.diff(&base).is_empty());
+
+        // Setting a key back to its old value is no change
+        let back = newer.insert(3, 3);
+        assert!(!back.diff(&newer).is_empty());
+        assert_eq!(back.diff(&newer.insert(3, 3)), vec![]);
+    }
+}
@@ -0,0 +1,378 @@
+/*
+banker's deque (Okasaki) -> the queue trick, but with both ends open
+    front: first element at the head, rear: last element at the head
+    deque is front ++ reverse(rear)
+
+each end is a persistent stack -> push/pop at either end is a prepend/uncons
+problem: popping from an end whose list is empty
+    queue only ever pops from front, so it can move all of rear over
+    a deque might pop from rear next -> moving everything just moves the problem
+keep the two sides balanced instead
+    invariant: neither side is more than C times the other (+1)
+    when one side breaks it, split the elements in half between both sides
+    O(n), but it takes O(n) ops on one side to unbalance again -> amortized O(1)
+with C = 3 and one side empty, the other has at most 1 element
+    so pop from an empty side just takes that single element from the other
+
+rebalancing is lazy like Queue's -> each end is a Side
+    all: the whole side as a Suspension, only worked out when needed
+    working: prefix of all that's already a real list, pushes and pops use it
+        a rebalance keeps the heavy side's old list as its working prefix -> nothing copied up front
+        that list is longer than the side now is -> working_len says how much of it counts
+    split only sets up Take/Rotate, they run once a side's working prefix runs out
+take/append/reverse copy elements -> T: Clone
+*/
+
+use super::suspension::Suspension;
+use super::Iter;
+use crate::third::List;
+use std::rc::Rc;
+
+const C: usize = 3;
+
+pub struct Deque<T> {
+    front: Side<T>,
+    rear: Side<T>,
+}
+
+struct Side<T> {
+    working: List<T>,
+    working_len: usize,
+    all: Rc<Suspension<T>>,
+    len: usize,
+}
+
+impl<T> Side<T> {
+    fn new() -> Self {
+        Side { working: List::new(), working_len: 0, all: Suspension::ready(List::new()), len: 0 }
+    }
+
+    fn head(&self) -> Option<&T> {
+        if self.working_len == 0 {
+            None
+        } else {
+            self.working.head()
+        }
+    }
+
+    fn push(&self, elem: T) -> Self {
+        let working = self.working.prepend(elem);
+        Side {
+            all: Suspension::cons(&working, &self.all),
+            working,
+            working_len: self.working_len + 1,
+            len: self.len + 1,
+        }
+    }
+
+    //may leave working empty -> Deque::check refills it
+    fn pop(&self) -> Option<(&T, Self)> {
+        if self.working_len == 0 {
+            return None;
+        }
+        let (elem, working) = self.working.uncons()?;
+        Some((
+            elem,
+            Side {
+                working,
+                working_len: self.working_len - 1,
+                all: Suspension::tail(&self.all),
+                len: self.len - 1,
+            },
+        ))
+    }
+}
+
+impl<T: Clone> Side<T> {
+    //working ran out -> the side's list is needed now
+    fn refill(self) -> Self {
+        if self.working_len > 0 || self.len == 0 {
+            return self;
+        }
+        let working = self.all.force().clone();
+        Side { working, working_len: self.len, all: self.all, len: self.len }
+    }
+}
+
+impl<T> Clone for Side<T> {
+    fn clone(&self) -> Self {
+        Side {
+            working: self.working.clone(),
+            working_len: self.working_len,
+            all: self.all.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Self {
+        Deque { front: Side::new(), rear: Side::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len + self.rear.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    //front side empty -> the only element (if any) sits in rear
+    pub fn peek_front(&self) -> Option<&T> {
+        self.front.head().or_else(|| self.rear.head())
+    }
+
+    pub fn peek_back(&self) -> Option<&T> {
+        self.rear.head().or_else(|| self.front.head())
+    }
+}
+
+impl<T: Clone> Deque<T> {
+    //forces both sides -> needs T: Clone like everything that touches a suspension
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.front.all.force(), self.rear.all.force())
+    }
+
+    //restore the balance invariant, then make sure neither side's working prefix is used up
+    fn check(front: Side<T>, rear: Side<T>) -> Self {
+        let n = front.len + rear.len;
+        let (front, rear) = if front.len > C * rear.len + 1 {
+            Self::split(&front, &rear, n / 2)
+        } else if rear.len > C * front.len + 1 {
+            let (rear, front) = Self::split(&rear, &front, n / 2);
+            (front, rear)
+        } else {
+            (front, rear)
+        };
+        Deque { front: front.refill(), rear: rear.refill() }
+    }
+
+    /*
+    keep the first half of the heavy side where it is
+        the rest gets reversed onto the end of the light side
+    forces both old sides (paid for by the ops since they were set up), only suspends the new ones
+        both old lists are prefixes of the new sides -> they become the working prefixes
+    */
+    fn split(heavy: &Side<T>, light: &Side<T>, keep: usize) -> (Side<T>, Side<T>) {
+        let moved = heavy.all.force().clone();
+        let onto = light.all.force().clone();
+        let light = Side {
+            working: onto.clone(),
+            working_len: light.len,
+            all: Suspension::rotate(onto, moved.clone(), keep),
+            len: light.len + heavy.len - keep,
+        };
+        let heavy = Side {
+            working: moved.clone(),
+            working_len: keep,
+            all: Suspension::take(moved, keep),
+            len: keep,
+        };
+        (heavy, light)
+    }
+
+    pub fn push_front(&self, elem: T) -> Self {
+        Self::check(self.front.push(elem), self.rear.clone())
+    }
+
+    pub fn push_back(&self, elem: T) -> Self {
+        Self::check(self.front.clone(), self.rear.push(elem))
+    }
+
+    pub fn pop_front(&self) -> Option<(&T, Self)> {
+        match self.front.pop() {
+            Some((elem, front)) => Some((elem, Self::check(front, self.rear.clone()))),
+            //front empty -> rear has at most one element, that's the front too
+            None => self.rear.head().map(|elem| (elem, Deque::new())),
+        }
+    }
+
+    pub fn pop_back(&self) -> Option<(&T, Self)> {
+        match self.rear.pop() {
+            Some((elem, rear)) => Some((elem, Self::check(self.front.clone(), rear))),
+            None => self.front.head().map(|elem| (elem, Deque::new())),
+        }
+    }
+}
+
+//O(1), shares both sides
+impl<T> Clone for Deque<T> {
+    fn clone(&self) -> Self {
+        Deque { front: self.front.clone(), rear: self.rear.clone() }
+    }
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//pushed onto the back in iteration order, like Queue
+impl<T: Clone> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().fold(Deque::new(), |deque, elem| deque.push_back(elem))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test::Counted;
+    use super::{Deque, C};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn to_vec(deque: &Deque<i32>) -> Vec<i32> {
+        deque.iter().copied().collect()
+    }
+
+    fn balanced(deque: &Deque<i32>) -> bool {
+        let (f, r) = (deque.front.len, deque.rear.len);
+        f <= C * r + 1 && r <= C * f + 1
+    }
+
+    #[test]
+    fn basics() {
+        let deque = Deque::new();
+        assert!(deque.is_empty());
+        assert_eq!(deque.peek_front(), None);
+        assert_eq!(deque.peek_back(), None);
+        assert!(deque.pop_front().is_none());
+        assert!(deque.pop_back().is_none());
+
+        let deque = deque.push_back(2).push_front(1).push_back(3);
+        assert_eq!(to_vec(&deque), vec![1, 2, 3]);
+        assert_eq!(deque.peek_front(), Some(&1));
+        assert_eq!(deque.peek_back(), Some(&3));
+
+        let (back, deque) = deque.pop_back().unwrap();
+        let (front, deque) = deque.pop_front().unwrap();
+        assert_eq!((*front, *back), (1, 3));
+        assert_eq!(to_vec(&deque), vec![2]);
+
+        let (last, deque) = deque.pop_back().unwrap();
+        assert_eq!(*last, 2);
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn one_sided() {
+        // Everything pushed at the front, everything popped from the back
+        let mut deque = Deque::new();
+        for i in 0..100 {
+            deque = deque.push_front(i);
+            assert!(balanced(&deque));
+        }
+        for i in 0..100 {
+            let (elem, rest) = deque.pop_back().map(|(elem, rest)| (*elem, rest)).unwrap();
+            assert_eq!(elem, i);
+            deque = rest;
+            assert!(balanced(&deque));
+        }
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn old_versions_unchanged() {
+        let v1: Deque<i32> = (1..=4).collect();
+        let v2 = v1.push_front(0);
+        let (_, v3) = v1.pop_back().unwrap();
+        let (_, v4) = v1.pop_front().unwrap();
+
+        assert_eq!(to_vec(&v1), vec![1, 2, 3, 4]);
+        assert_eq!(to_vec(&v2), vec![0, 1, 2, 3, 4]);
+        assert_eq!(to_vec(&v3), vec![1, 2, 3]);
+        assert_eq!(to_vec(&v4), vec![2, 3, 4]);
+        // Untouched side is shared as is
+        assert!(Rc::ptr_eq(&v2.rear.all, &v1.rear.all));
+    }
+
+    #[test]
+    fn rebalance_not_redone() {
+        let clones = Rc::new(Cell::new(0));
+        let mut v = Deque::new();
+        for i in 0..13 {
+            v = v.push_front(Counted::new(i, &clones));
+        }
+        // Balanced now, one more pop from the light side tips it over
+        assert_eq!((v.front.len, v.rear.len), (10, 3));
+        v.iter().count();
+        clones.set(0);
+
+        // Popping the same old version over and over only sets up the rebalance
+        for _ in 0..100 {
+            let (elem, rest) = v.pop_back().unwrap();
+            assert_eq!(elem.value, 0);
+            assert_eq!((rest.front.len, rest.rear.len), (6, 6));
+        }
+        assert_eq!(clones.get(), 0);
+
+        // Forcing it copies 6 kept, 2 appended onto, 4 moved
+        let (_, w) = v.pop_back().unwrap();
+        let values: Vec<i32> = w.iter().map(|c| c.value).collect();
+        assert_eq!(values, (1..13).rev().collect::<Vec<_>>());
+        assert_eq!(clones.get(), 12);
+
+        // Every version sharing that rebalance finds it already done
+        for _ in 0..100 {
+            let (front, _) = w.pop_front().unwrap();
+            let (back, _) = w.pop_back().unwrap();
+            assert_eq!((front.value, back.value), (12, 1));
+        }
+        assert_eq!(w.clone().iter().count(), 12);
+        assert_eq!(clones.get(), 12);
+    }
+
+    #[test]
+    fn matches_vecdeque() {
+        use std::collections::VecDeque;
+
+        // Small xorshift, same idea as sixth.rs's stress test
+        let mut seed = 0x2545_f491_u32;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+
+        let mut deque = Deque::new();
+        let mut model = VecDeque::new();
+        for i in 0..2000 {
+            match next() % 4 {
+                0 => {
+                    deque = deque.push_front(i);
+                    model.push_front(i);
+                }
+                1 => {
+                    deque = deque.push_back(i);
+                    model.push_back(i);
+                }
+                2 => {
+                    let expected = model.pop_front();
+                    if let Some((elem, rest)) = deque.pop_front().map(|(e, r)| (*e, r)) {
+                        assert_eq!(Some(elem), expected);
+                        deque = rest;
+                    } else {
+                        assert_eq!(expected, None);
+                    }
+                }
+                _ => {
+                    let expected = model.pop_back();
+                    if let Some((elem, rest)) = deque.pop_back().map(|(e, r)| (*e, r)) {
+                        assert_eq!(Some(elem), expected);
+                        deque = rest;
+                    } else {
+                        assert_eq!(expected, None);
+                    }
+                }
+            }
+            assert_eq!(deque.len(), model.len());
+            assert_eq!(deque.peek_front(), model.front());
+            assert_eq!(deque.peek_back(), model.back());
+            assert!(balanced(&deque));
+        }
+        assert_eq!(to_vec(&deque), model.into_iter().collect::<Vec<_>>());
+    }
+}
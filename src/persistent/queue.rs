@@ -0,0 +1,242 @@
+/*
+banker's queue (Okasaki) -> FIFO out of two persistent stacks
+    front: elements to pop, oldest at the head
+    rear: elements just pushed, newest at the head
+    queue is front ++ reverse(rear)
+
+push_back prepends to rear, pop_front takes front's head -> both O(1)
+rebalance when rear gets longer than front
+    front = front ++ reverse(rear), rear = empty
+    O(n), but n pushes had to happen to make rear that long -> amortized O(1)
+invariant rear.len() <= front_len
+    so front is only ever empty when the whole queue is
+
+rebalance is lazy (Okasaki's physicist's queue) -> bound holds even when old versions get reused
+    front is a Suspension: front ++ reverse(rear) only runs once something needs it
+    working: prefix of front that's already a real list, pops come from here
+        runs out -> force front once and carry on from that
+    a rebalance forces the previous front, the pops since that one was set up paid for it
+    every version made from a rebalance shares its Suspension -> forced once, not once per version
+        so popping the same old version over and over never redoes the copy
+
+front's list may not exist yet -> its length is kept alongside, rear's comes from third::List::len
+append/reverse copy elements -> T: Clone
+*/
+
+use super::suspension::Suspension;
+use super::Iter;
+use crate::third::List;
+use std::rc::Rc;
+
+pub struct Queue<T> {
+    working: List<T>,
+    front: Rc<Suspension<T>>,
+    front_len: usize,
+    rear: List<T>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue {
+            working: List::new(),
+            front: Suspension::ready(List::new()),
+            front_len: 0,
+            rear: List::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front_len + self.rear.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front_len == 0
+    }
+
+    //oldest element -> working's head (working only empty when front is)
+    pub fn peek(&self) -> Option<&T> {
+        self.working.head()
+    }
+}
+
+impl<T: Clone> Queue<T> {
+    //forces front -> needs T: Clone like everything that touches the suspension
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.front.force(), &self.rear)
+    }
+
+    //restore rear.len() <= front_len, rebalance only gets set up here
+    fn check(working: List<T>, front: Rc<Suspension<T>>, front_len: usize, rear: List<T>) -> Self {
+        if rear.len() <= front_len {
+            return Self::check_working(working, front, front_len, rear);
+        }
+        let old = front.force().clone();
+        let front_len = front_len + rear.len();
+        let front = Suspension::rotate(old.clone(), rear, 0);
+        //old front is a prefix of the new one -> pops keep going from it
+        Self::check_working(old, front, front_len, List::new())
+    }
+
+    //working ran out -> front's list is needed now
+    fn check_working(working: List<T>, front: Rc<Suspension<T>>, front_len: usize, rear: List<T>) -> Self {
+        let working = if working.is_empty() { front.force().clone() } else { working };
+        Queue { working, front, front_len, rear }
+    }
+
+    pub fn push_back(&self, elem: T) -> Self {
+        Self::check(self.working.clone(), self.front.clone(), self.front_len, self.rear.prepend(elem))
+    }
+
+    //oldest element and the queue without it, like third::List::uncons
+    pub fn pop_front(&self) -> Option<(&T, Self)> {
+        let (elem, working) = self.working.uncons()?;
+        let front = Suspension::tail(&self.front);
+        Some((elem, Self::check(working, front, self.front_len - 1, self.rear.clone())))
+    }
+}
+
+//O(1), shares both lists and the suspension
+impl<T> Clone for Queue<T> {
+    fn clone(&self) -> Self {
+        Queue {
+            working: self.working.clone(),
+            front: self.front.clone(),
+            front_len: self.front_len,
+            rear: self.rear.clone(),
+        }
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//pushed in iteration order -> first item comes out first
+impl<T: Clone> FromIterator<T> for Queue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().fold(Queue::new(), |queue, elem| queue.push_back(elem))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::test::Counted;
+    use super::Queue;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn to_vec(queue: &Queue<i32>) -> Vec<i32> {
+        queue.iter().copied().collect()
+    }
+
+    #[test]
+    fn basics() {
+        let queue = Queue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.peek(), None);
+        assert!(queue.pop_front().is_none());
+
+        let queue = queue.push_back(1).push_back(2).push_back(3);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.peek(), Some(&1));
+
+        let (elem, queue) = queue.pop_front().unwrap();
+        assert_eq!(*elem, 1);
+        let queue = queue.push_back(4);
+        assert_eq!(to_vec(&queue), vec![2, 3, 4]);
+
+        let (a, queue) = queue.pop_front().unwrap();
+        let (b, queue) = queue.pop_front().unwrap();
+        let (c, queue) = queue.pop_front().unwrap();
+        assert_eq!((*a, *b, *c), (2, 3, 4));
+        assert!(queue.is_empty());
+        assert!(queue.pop_front().is_none());
+    }
+
+    #[test]
+    fn old_versions_unchanged() {
+        let v1: Queue<i32> = (1..=3).collect();
+        let v2 = v1.push_back(4);
+        let (_, v3) = v2.pop_front().unwrap();
+        let v4 = v1.push_back(40);
+
+        assert_eq!(to_vec(&v1), vec![1, 2, 3]);
+        assert_eq!(to_vec(&v2), vec![1, 2, 3, 4]);
+        assert_eq!(to_vec(&v3), vec![2, 3, 4]);
+        assert_eq!(to_vec(&v4), vec![1, 2, 3, 40]);
+    }
+
+    #[test]
+    fn shares_structure() {
+        let clones = Rc::new(Cell::new(0));
+        let v1: Queue<Counted> = (0..8).map(|i| Counted::new(i, &clones)).collect();
+        v1.iter().count();
+        clones.set(0);
+
+        // Below the rebalance -> same front, rear grown by one node
+        let v2 = v1.push_back(Counted::new(8, &clones));
+        assert!(Rc::ptr_eq(&v2.front, &v1.front));
+        assert_eq!(v2.rear.common_suffix_len(&v1.rear), v1.rear.len());
+
+        let (_, v3) = v1.pop_front().unwrap();
+        assert_eq!(v3.working.common_suffix_len(&v1.working), v3.working.len());
+        assert_eq!(clones.get(), 0);
+    }
+
+    #[test]
+    fn rebalance_not_redone() {
+        let clones = Rc::new(Cell::new(0));
+        let v: Queue<Counted> = (0..14).map(|i| Counted::new(i, &clones)).collect();
+        // Next pop leaves rear longer than front
+        assert_eq!((v.front_len, v.rear.len()), (7, 7));
+        v.iter().count();
+        clones.set(0);
+
+        // Popping the same old version over and over only sets up the rebalance
+        for _ in 0..100 {
+            let (elem, rest) = v.pop_front().unwrap();
+            assert_eq!(elem.value, 0);
+            assert_eq!((rest.front_len, rest.rear.len()), (13, 0));
+        }
+        assert_eq!(clones.get(), 0);
+
+        // Forcing it copies every element once (6 appended, 7 reversed)
+        let (_, w) = v.pop_front().unwrap();
+        let values: Vec<i32> = w.iter().map(|c| c.value).collect();
+        assert_eq!(values, (1..14).collect::<Vec<_>>());
+        assert_eq!(clones.get(), 13);
+
+        // Every version sharing that rebalance finds it already done
+        let mut rest = w.clone();
+        while let Some(next) = rest.pop_front().map(|(_, next)| next) {
+            rest = next;
+        }
+        assert_eq!(w.iter().count(), 13);
+        assert_eq!(clones.get(), 13);
+    }
+
+    #[test]
+    fn matches_vecdeque() {
+        use std::collections::VecDeque;
+
+        let mut queue = Queue::new();
+        let mut model = VecDeque::new();
+        for i in 0..1000 {
+            if i % 3 == 2 {
+                let popped = queue.pop_front().map(|(elem, rest)| (*elem, rest));
+                assert_eq!(popped.as_ref().map(|(elem, _)| *elem), model.pop_front());
+                if let Some((_, rest)) = popped {
+                    queue = rest;
+                }
+            } else {
+                queue = queue.push_back(i);
+                model.push_back(i);
+            }
+            assert_eq!(queue.len(), model.len());
+            assert!(queue.rear.len() <= queue.front_len);
+        }
+        assert_eq!(to_vec(&queue), model.into_iter().collect::<Vec<_>>());
+    }
+}
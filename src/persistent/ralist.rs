@@ -0,0 +1,387 @@
+/*
+skew binary random access list (Okasaki) -> persistent list with O(log n) indexing
+    third::List is O(n) to get to element i, one node at a time
+    here elements live in complete binary trees, so a lookup can skip whole trees
+
+spine: a third::List of trees, smallest first
+    every tree is complete -> size is 2^k - 1 (1, 3, 7, 15, ...)
+    sizes strictly increase, except the first two may be equal
+    that's skew binary numbers: n written with digits 2^k - 1, only the lowest one doubled
+element order: first tree before second, and inside a tree root, then left, then right
+
+prepend: if the first two trees have the same size w -> new root on top of both (size 2w + 1)
+    otherwise -> a single leaf in front
+    both O(1), like the skew binary +1 (no carries ripple through)
+tail: undo that -> leaf just goes, a root splits back into its two children
+get(i): skip whole trees while i is past them -> O(log n) trees
+    then walk down one path inside the tree -> O(log n) levels
+update(i): same walk, but copies the path it takes -> new version shares every other node
+
+pointer family is third's -> RaList over Rc, sync::RaList over Arc
+    spine is a third::GenericList with the same family, trees use P::Pointer too
+*/
+
+use crate::third::{self, ArcFamily, GenericList, PointerFamily, RcFamily};
+
+pub struct GenericRaList<T, P: PointerFamily> {
+    spine: GenericList<Digit<T, P>, P>,
+}
+
+pub type RaList<T> = GenericRaList<T, RcFamily>;
+
+//one spine entry -> tree plus its size, so nobody has to count nodes
+struct Digit<T, P: PointerFamily> {
+    size: usize,
+    tree: P::Pointer<Tree<T, P>>,
+}
+
+enum Tree<T, P: PointerFamily> {
+    Leaf(T),
+    Node(T, P::Pointer<Tree<T, P>>, P::Pointer<Tree<T, P>>),
+}
+
+//Pointer is Clone for any U -> no need for T: Clone, derive would ask for it
+impl<T, P: PointerFamily> Clone for Digit<T, P> {
+    fn clone(&self) -> Self {
+        Digit { size: self.size, tree: self.tree.clone() }
+    }
+}
+
+impl<T, P: PointerFamily> Tree<T, P> {
+    fn elem(&self) -> &T {
+        match self {
+            Tree::Leaf(elem) | Tree::Node(elem, _, _) => elem,
+        }
+    }
+
+    //i-th element (root first) of a complete tree holding size elements
+    fn get(&self, mut size: usize, mut i: usize) -> &T {
+        let mut tree = self;
+        loop {
+            match tree {
+                Tree::Node(_, left, right) if i > 0 => {
+                    size /= 2;
+                    if i <= size {
+                        tree = left;
+                        i -= 1;
+                    } else {
+                        tree = right;
+                        i -= 1 + size;
+                    }
+                }
+                //i == 0, or a leaf (where i can only be 0)
+                _ => return tree.elem(),
+            }
+        }
+    }
+
+    //path copy -> recursion only goes as deep as the tree, O(log n)
+    fn update(&self, size: usize, i: usize, elem: T) -> Self
+    where
+        T: Clone,
+    {
+        match self {
+            Tree::Leaf(_) => Tree::Leaf(elem),
+            Tree::Node(_, left, right) if i == 0 => Tree::Node(elem, left.clone(), right.clone()),
+            Tree::Node(root, left, right) => {
+                let half = size / 2;
+                if i <= half {
+                    let left = P::new(left.update(half, i - 1, elem));
+                    Tree::Node(root.clone(), left, right.clone())
+                } else {
+                    let right = P::new(right.update(half, i - 1 - half, elem));
+                    Tree::Node(root.clone(), left.clone(), right)
+                }
+            }
+        }
+    }
+}
+
+impl<T, P: PointerFamily> GenericRaList<T, P> {
+    pub fn new() -> Self {
+        GenericRaList { spine: GenericList::new() }
+    }
+
+    pub fn prepend(&self, elem: T) -> Self {
+        let rest = self.spine.tail();
+        match (self.spine.head(), rest.head()) {
+            (Some(first), Some(second)) if first.size == second.size => {
+                let tree = Tree::Node(elem, first.tree.clone(), second.tree.clone());
+                let digit = Digit { size: 1 + first.size + second.size, tree: P::new(tree) };
+                GenericRaList { spine: rest.tail().prepend(digit) }
+            }
+            _ => {
+                let digit = Digit { size: 1, tree: P::new(Tree::Leaf(elem)) };
+                GenericRaList { spine: self.spine.prepend(digit) }
+            }
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.spine.head().map(|digit| digit.tree.elem())
+    }
+
+    pub fn tail(&self) -> Self {
+        let Some((first, rest)) = self.spine.uncons() else {
+            return Self::new();
+        };
+        match &*first.tree {
+            Tree::Leaf(_) => GenericRaList { spine: rest },
+            Tree::Node(_, left, right) => {
+                let size = first.size / 2;
+                let spine = rest
+                    .prepend(Digit { size, tree: right.clone() })
+                    .prepend(Digit { size, tree: left.clone() });
+                GenericRaList { spine }
+            }
+        }
+    }
+
+    //one size per tree -> O(log n)
+    pub fn len(&self) -> usize {
+        self.spine.iter().map(|digit| digit.size).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spine.is_empty()
+    }
+
+    pub fn get(&self, mut i: usize) -> Option<&T> {
+        for digit in self.spine.iter() {
+            if i < digit.size {
+                return Some(digit.tree.get(digit.size, i));
+            }
+            i -= digit.size;
+        }
+        None
+    }
+
+    //new version with element i replaced, None if out of bounds
+        //spine entries before the changed tree are copied (cheap, just size + pointer)
+        //spine after it and every untouched subtree are shared
+    pub fn update(&self, mut i: usize, elem: T) -> Option<Self>
+    where
+        T: Clone,
+    {
+        let mut before = Vec::new();
+        for (k, digit) in self.spine.iter().enumerate() {
+            if i < digit.size {
+                let tree = P::new(digit.tree.update(digit.size, i, elem));
+                let spine = self.spine.skip(k + 1).prepend(Digit { size: digit.size, tree });
+                let spine = before.into_iter().rev().fold(spine, |spine, d| spine.prepend(d));
+                return Some(GenericRaList { spine });
+            }
+            i -= digit.size;
+            before.push(digit.clone());
+        }
+        None
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        Iter { spine: self.spine.iter(), trees: Vec::new() }
+    }
+}
+
+//O(1), shares the whole spine
+impl<T, P: PointerFamily> Clone for GenericRaList<T, P> {
+    fn clone(&self) -> Self {
+        GenericRaList { spine: self.spine.clone() }
+    }
+}
+
+impl<T, P: PointerFamily> Default for GenericRaList<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//prepend back to front -> first item ends up at index 0
+impl<T, P: PointerFamily> FromIterator<T> for GenericRaList<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let elems: Vec<T> = iter.into_iter().collect();
+        elems.into_iter().rev().fold(Self::new(), |list, elem| list.prepend(elem))
+    }
+}
+
+//conversions copy elements -> the two layouts have no nodes in common
+impl<T: Clone, P: PointerFamily> From<&GenericList<T, P>> for GenericRaList<T, P> {
+    fn from(list: &GenericList<T, P>) -> Self {
+        list.iter().cloned().collect()
+    }
+}
+
+impl<T: Clone, P: PointerFamily> From<&GenericRaList<T, P>> for GenericList<T, P> {
+    fn from(list: &GenericRaList<T, P>) -> Self {
+        let elems: Vec<&T> = list.iter().collect();
+        elems.into_iter().rev().fold(GenericList::new(), |list, elem| list.prepend(elem.clone()))
+    }
+}
+
+/*
+front to back: trees in spine order, each tree root, left, right (preorder)
+explicit stack of subtrees still to visit -> at most O(log n) of them
+*/
+pub struct Iter<'a, T, P: PointerFamily = RcFamily> {
+    spine: third::Iter<'a, Digit<T, P>, P>,
+    trees: Vec<&'a Tree<T, P>>,
+}
+
+impl<'a, T, P: PointerFamily> Iterator for Iter<'a, T, P> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.trees.pop() {
+                Some(Tree::Leaf(elem)) => return Some(elem),
+                Some(Tree::Node(elem, left, right)) => {
+                    self.trees.push(right);
+                    self.trees.push(left);
+                    return Some(elem);
+                }
+                None => self.trees.push(&self.spine.next()?.tree),
+            }
+        }
+    }
+}
+
+//Arc flavour, same idea as third::sync
+pub mod sync {
+    pub type RaList<T> = super::GenericRaList<T, super::ArcFamily>;
+    pub type Iter<'a, T> = super::Iter<'a, T, super::ArcFamily>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sync, RaList};
+    use crate::third;
+
+    fn sizes(list: &RaList<i32>) -> Vec<usize> {
+        list.spine.iter().map(|digit| digit.size).collect()
+    }
+
+    #[test]
+    fn basics() {
+        let list = RaList::new();
+        assert_eq!(list.head(), None);
+        assert!(list.is_empty());
+        assert!(list.tail().is_empty());
+
+        let list = list.prepend(3).prepend(2).prepend(1);
+        assert_eq!(list.head(), Some(&1));
+        assert_eq!(list.len(), 3);
+
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&2));
+        let list = list.tail();
+        assert_eq!(list.head(), Some(&3));
+        let list = list.tail();
+        assert_eq!(list.head(), None);
+    }
+
+    #[test]
+    fn skew_shape() {
+        // 1, then 1+1, then merged into 3, ...
+        let mut list = RaList::new();
+        let mut expected = vec![
+            vec![1],
+            vec![1, 1],
+            vec![3],
+            vec![1, 3],
+            vec![1, 1, 3],
+            vec![3, 3],
+            vec![7],
+        ]
+        .into_iter();
+        for i in 0..7 {
+            list = list.prepend(i);
+            assert_eq!(sizes(&list), expected.next().unwrap());
+        }
+
+        // tail undoes prepend exactly
+        let mut list: RaList<i32> = (0..100).collect();
+        for n in (0..100).rev() {
+            let tail = list.tail();
+            assert_eq!(sizes(&tail.prepend(0)), sizes(&list));
+            assert_eq!(tail.len(), n);
+            list = tail;
+        }
+    }
+
+    #[test]
+    fn get_and_iter() {
+        for n in [0, 1, 2, 3, 7, 10, 64, 100] {
+            let list: RaList<usize> = (0..n).collect();
+            assert_eq!(list.len(), n);
+            for i in 0..n {
+                assert_eq!(list.get(i), Some(&i));
+            }
+            assert_eq!(list.get(n), None);
+            assert_eq!(list.iter().copied().collect::<Vec<_>>(), (0..n).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn update() {
+        let v1: RaList<i32> = (0..20).collect();
+        assert!(v1.update(20, 0).is_none());
+
+        let mut model: Vec<i32> = (0..20).collect();
+        let mut list = v1.clone();
+        for i in (0..20).step_by(3) {
+            list = list.update(i, -(i as i32)).unwrap();
+            model[i] = -(i as i32);
+            assert_eq!(list.iter().copied().collect::<Vec<_>>(), model);
+        }
+
+        // Old version untouched
+        assert_eq!(v1.iter().copied().collect::<Vec<_>>(), (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn update_shares_structure() {
+        use std::rc::Rc;
+
+        let tracker = Rc::new(());
+        let v1: RaList<Rc<()>> = (0..15).map(|_| tracker.clone()).collect();
+        assert_eq!(Rc::strong_count(&tracker), 16);
+
+        // One tree of 15 -> updating the last element copies the 3 elements on its path
+        let v2 = v1.update(14, Rc::new(())).unwrap();
+        assert_eq!(Rc::strong_count(&tracker), 16 + 3);
+
+        drop(v1);
+        assert_eq!(Rc::strong_count(&tracker), 15);
+        drop(v2);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn conversions() {
+        let list = third::List::new().prepend(3).prepend(2).prepend(1);
+        let ra = RaList::from(&list);
+        assert_eq!(ra.get(2), Some(&3));
+
+        let back = third::List::from(&ra.prepend(0));
+        assert_eq!(back.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn sync_shared_between_threads() {
+        use std::thread;
+
+        let list: sync::RaList<i32> = (0..50).collect();
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let list = list.clone();
+                thread::spawn(move || {
+                    let list = list.update(t, 100 + t as i32).unwrap();
+                    (list.get(t).copied(), list.get(49).copied())
+                })
+            })
+            .collect();
+        for (t, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), (Some(100 + t as i32), Some(49)));
+        }
+        assert_eq!(list.get(0), Some(&0));
+    }
+}
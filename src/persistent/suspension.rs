@@ -0,0 +1,189 @@
+/*
+suspended third::List -> worked out the first time someone forces it, then remembered
+    every version holding the same Rc<Suspension> shares the result
+    -> a rebalance set up once is paid for once, however many old versions force it
+
+pending work is plain data, not a closure -> no 'static needed on T
+    Tail/Cons: one pop/push done on top of another suspension that isn't forced yet
+    Take/Rotate: the copies a rebalance makes
+pops stack one Tail per pop -> force walks the chain with a loop, not recursion
+    drop does the same, a long unforced chain would otherwise recurse once per link
+*/
+
+use crate::third::List;
+use std::cell::{Cell, OnceCell};
+use std::rc::Rc;
+
+pub(super) struct Suspension<T> {
+    value: OnceCell<List<T>>,
+    //Some until forced, taken out by force
+    pending: Cell<Option<Pending<T>>>,
+}
+
+enum Pending<T> {
+    //force(inner) without its head
+    Tail(Rc<Suspension<T>>),
+    //pushed's head on top of force(inner) -> element only copied if this gets forced
+    Cons(List<T>, Rc<Suspension<T>>),
+    //first n elements of list
+    Take(List<T>, usize),
+    //front ++ reverse(back without its first skip elements)
+    Rotate { front: List<T>, back: List<T>, skip: usize },
+}
+
+//what a Tail/Cons does to its inner list once that's known
+enum Step<T> {
+    Tail,
+    Cons(List<T>),
+}
+
+impl<T> Pending<T> {
+    fn into_inner(self) -> Option<Rc<Suspension<T>>> {
+        match self {
+            Pending::Tail(inner) | Pending::Cons(_, inner) => Some(inner),
+            Pending::Take(..) | Pending::Rotate { .. } => None,
+        }
+    }
+}
+
+impl<T> Suspension<T> {
+    fn new(pending: Pending<T>) -> Rc<Self> {
+        Rc::new(Suspension { value: OnceCell::new(), pending: Cell::new(Some(pending)) })
+    }
+
+    pub(super) fn ready(list: List<T>) -> Rc<Self> {
+        Rc::new(Suspension { value: OnceCell::from(list), pending: Cell::new(None) })
+    }
+
+    //inner already forced -> tail is O(1) anyway, no point suspending it
+    pub(super) fn tail(inner: &Rc<Self>) -> Rc<Self> {
+        match inner.value.get() {
+            Some(list) => Self::ready(list.tail()),
+            None => Self::new(Pending::Tail(inner.clone())),
+        }
+    }
+
+    //pushed: list whose head is the new element (the pusher's own working list)
+    pub(super) fn cons(pushed: &List<T>, inner: &Rc<Self>) -> Rc<Self> {
+        Self::new(Pending::Cons(pushed.clone(), inner.clone()))
+    }
+
+    pub(super) fn take(list: List<T>, n: usize) -> Rc<Self> {
+        Self::new(Pending::Take(list, n))
+    }
+
+    pub(super) fn rotate(front: List<T>, back: List<T>, skip: usize) -> Rc<Self> {
+        Self::new(Pending::Rotate { front, back, skip })
+    }
+
+    fn take_pending(&self) -> Pending<T> {
+        self.pending.take().expect("suspension forced while it was being forced")
+    }
+}
+
+impl<T: Clone> Suspension<T> {
+    pub(super) fn force(&self) -> &List<T> {
+        if let Some(list) = self.value.get() {
+            return list;
+        }
+
+        /*
+        walk down Tail/Cons links until something is known
+            below[i] is the suspension steps[i] is applied to
+            list ends up as the value of below.last() (or of self if nothing is below)
+        */
+        let mut steps = Vec::new();
+        let mut below: Vec<Rc<Self>> = Vec::new();
+        let mut pending = self.take_pending();
+        let mut list = loop {
+            let (step, inner) = match pending {
+                Pending::Tail(inner) => (Step::Tail, inner),
+                Pending::Cons(pushed, inner) => (Step::Cons(pushed), inner),
+                Pending::Take(list, n) => break list.take(n),
+                Pending::Rotate { front, back, skip } => {
+                    break front.append(&back.skip(skip).reverse());
+                }
+            };
+            steps.push(step);
+            if let Some(list) = inner.value.get() {
+                let list = list.clone();
+                below.push(inner);
+                break list;
+            }
+            pending = inner.take_pending();
+            below.push(inner);
+        };
+
+        //back up the chain, remembering every value on the way -> each link forced once
+        for (step, inner) in steps.into_iter().zip(below).rev() {
+            //already set if inner was forced before we got here
+            let _ = inner.value.set(list.clone());
+            list = match step {
+                Step::Tail => list.tail(),
+                Step::Cons(pushed) => list.prepend(pushed.head().unwrap().clone()),
+            };
+        }
+        let _ = self.value.set(list);
+        self.value.get().unwrap()
+    }
+}
+
+impl<T> Drop for Suspension<T> {
+    fn drop(&mut self) {
+        let mut next = self.pending.take().and_then(Pending::into_inner);
+        while let Some(inner) = next {
+            next = match Rc::try_unwrap(inner) {
+                Ok(suspension) => suspension.pending.take().and_then(Pending::into_inner),
+                //someone else still holds the rest of the chain
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Suspension;
+    use crate::third::List;
+
+    fn to_vec(suspension: &Suspension<i32>) -> Vec<i32> {
+        suspension.force().iter().copied().collect()
+    }
+
+    #[test]
+    fn pending_work() {
+        let list: List<i32> = List::new().prepend(3).prepend(2).prepend(1);
+        assert_eq!(to_vec(&Suspension::take(list.clone(), 2)), vec![1, 2]);
+        assert_eq!(to_vec(&Suspension::rotate(list.clone(), list.clone(), 1)), vec![1, 2, 3, 3, 2]);
+
+        let base = Suspension::rotate(List::new(), list.clone(), 0);
+        let popped = Suspension::tail(&base);
+        let pushed = List::new().prepend(9);
+        let both = Suspension::cons(&pushed, &popped);
+        assert_eq!(to_vec(&both), vec![9, 2, 1]);
+        // Forcing the top forced everything under it too
+        assert_eq!(to_vec(&popped), vec![2, 1]);
+        assert_eq!(to_vec(&base), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn long_chain() {
+        let list: List<i32> = (0..100_000).fold(List::new(), |list, i| list.prepend(i));
+        let base = Suspension::rotate(List::new(), list, 0);
+
+        let mut top = Suspension::tail(&base);
+        for _ in 1..99_999 {
+            top = Suspension::tail(&top);
+        }
+        assert_eq!(to_vec(&top), vec![99_999]);
+
+        // Unforced chain of the same length -> dropping it mustn't recurse per link
+        let base = Suspension::<i32>::take(List::new(), 0);
+        let mut top = Suspension::tail(&base);
+        for _ in 0..100_000 {
+            top = Suspension::tail(&top);
+        }
+        drop(base);
+        drop(top);
+    }
+}
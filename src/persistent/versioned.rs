@@ -0,0 +1,118 @@
+/*
+versioned key-value store -> every change makes a new AssocMap, all of them are kept
+    versions share pairs with each other, so keeping history costs about one pair per change
+    version ids are indices into the history, 0 is the empty map
+diff between any two versions goes through AssocMap::diff
+    versions made from each other share a long suffix -> only the few new pairs get looked at
+*/
+
+use super::assoc_map::{AssocMap, Change};
+
+pub struct Versioned<K, V> {
+    versions: Vec<AssocMap<K, V>>,
+}
+
+impl<K, V> Versioned<K, V> {
+    pub fn new() -> Self {
+        Versioned { versions: vec![AssocMap::new()] }
+    }
+
+    pub fn current(&self) -> &AssocMap<K, V> {
+        self.versions.last().unwrap()
+    }
+
+    pub fn current_version(&self) -> usize {
+        self.versions.len() - 1
+    }
+
+    pub fn version(&self, version: usize) -> Option<&AssocMap<K, V>> {
+        self.versions.get(version)
+    }
+
+    //any map can become the next version, e.g. an old one to roll back
+    pub fn commit(&mut self, map: AssocMap<K, V>) -> usize {
+        self.versions.push(map);
+        self.current_version()
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> usize {
+        let map = self.current().insert(key, value);
+        self.commit(map)
+    }
+}
+
+impl<K: Clone, V: Clone> Versioned<K, V> {
+    pub fn remove(&mut self, key: &K) -> usize
+    where
+        K: PartialEq,
+    {
+        let map = self.current().remove(key);
+        self.commit(map)
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> Versioned<K, V> {
+    //None if either version doesn't exist
+    pub fn diff(&self, from: usize, to: usize) -> Option<Vec<Change<'_, K, V>>> {
+        Some(self.version(from)?.diff(self.version(to)?))
+    }
+}
+
+impl<K, V> Default for Versioned<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Versioned;
+    use crate::persistent::assoc_map::Change;
+
+    #[test]
+    fn history() {
+        let mut store = Versioned::new();
+        assert_eq!(store.current_version(), 0);
+
+        let v1 = store.insert("dark_mode", false);
+        let v2 = store.insert("beta", true);
+        let v3 = store.insert("dark_mode", true);
+        let v4 = store.remove(&"beta");
+        assert_eq!((v1, v2, v3, v4), (1, 2, 3, 4));
+
+        assert_eq!(store.version(0).unwrap().get("dark_mode"), None);
+        assert_eq!(store.version(1).unwrap().get("dark_mode"), Some(&false));
+        assert_eq!(store.version(3).unwrap().get("beta"), Some(&true));
+        assert_eq!(store.current().get("beta"), None);
+        assert_eq!(store.current().get("dark_mode"), Some(&true));
+        assert!(store.version(5).is_none());
+    }
+
+    #[test]
+    fn diff() {
+        let mut store = Versioned::new();
+        store.insert("a", 1);
+        store.insert("b", 2);
+        store.insert("a", 3);
+        store.remove(&"b");
+
+        assert_eq!(store.diff(0, 1), Some(vec![Change::Added(&"a", &1)]));
+        assert_eq!(store.diff(1, 3), Some(vec![Change::Changed(&"a", &1, &3), Change::Added(&"b", &2)]));
+        assert_eq!(store.diff(3, 4), Some(vec![Change::Removed(&"b", &2)]));
+        assert_eq!(store.diff(4, 1), Some(vec![Change::Changed(&"a", &3, &1)]));
+        assert_eq!(store.diff(0, 9), None);
+    }
+
+    #[test]
+    fn rollback() {
+        let mut store = Versioned::new();
+        store.insert("a", 1);
+        store.insert("a", 2);
+
+        let old = store.version(1).unwrap().clone();
+        let v3 = store.commit(old);
+        assert_eq!(store.current().get("a"), Some(&1));
+        // Same map as version 1 -> nothing to diff
+        assert_eq!(store.diff(1, v3), Some(vec![]));
+    }
+}
@@ -1,4 +1,6 @@
-use std::mem;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
 pub struct List<T> {
     head: Link<T>,
@@ -24,7 +26,7 @@ impl<T> List<T> {
 
     pub fn push(&mut self, elem: T) {
         let new_node = Box::new(Node {
-            elem: elem,
+            elem,
             //mem::replace(&mut option, None) very comon
                 //method 'take' is the same
             next: self.head.take(),
@@ -33,7 +35,7 @@ impl<T> List<T> {
         self.head = Some(new_node);
     }
 
-    pub fn pop(&mut self) -> Option<i32> {
+    pub fn pop(&mut self) -> Option<T> {
         //match option {None => None, Some(x) => Some(y)} ubiqituous
             //method 'map' is same -> takes function
 
@@ -66,10 +68,6 @@ impl<T> List<T> {
         })
     }
 
-    pub fn into_iter(self) -> IntoIter<T> {
-        IntoIter(self)
-    }
-
     /* We declare a fresh lifetime here for the *exact* borrow that
     creates the iter. Now &self needs to be valid as long as the
     Iter is around.
@@ -91,6 +89,11 @@ impl<T> List<T> {
         IterMut { next: self.head.as_deref_mut() }
     }
 
+    //cursor starts on the ghost in front of the head
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut { cur: None, next: Some(&mut self.head) }
+    }
+
 }
 
 impl<T> Drop for List<T> {
@@ -126,12 +129,35 @@ iterate collections using Iterator trait
     //useful for trivial wrappers around other types
 pub struct IntoIter<T>(List<T>);
 
-//also include below method within List implementation
 /*
-    pub fn into_iter(self) -> IntoIter<T> {
+into_iter comes from the IntoIterator trait instead of an inherent method
+    for loops call IntoIterator::into_iter on whatever they're given
+implement it for List<T>, &List<T> and &mut List<T>
+    -> 'for x in list', 'for x in &list', 'for x in &mut list' all work
+*/
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
         IntoIter(self)
     }
-*/
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
 
 impl<T> Iterator for IntoIter<T> {
     type Item = T;
@@ -265,6 +291,205 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 }
 
 
+/*
+std traits -> let List plug into the rest of the standard library
+
+building from an iterator works like Vec used as a stack
+    each element is pushed in turn -> last one ends up on top
+    List::from(vec![1, 2, 3]).pop() == Some(3), same as Vec::pop
+Clone keeps the exact same order instead
+    walks self once, appending at a tail link -> no recursion, no reversal
+comparisons and hashing go through iter() -> head first
+*/
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        let mut new_list = List::new();
+        //tail is always the empty link at the end of new_list
+        let mut tail = &mut new_list.head;
+        for elem in self.iter() {
+            let node = tail.insert(Box::new(Node {
+                elem: elem.clone(),
+                next: None,
+            }));
+            tail = &mut node.next;
+        }
+        new_list
+    }
+}
+
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+//extending from references -> copy each element in, like Vec
+impl<'a, T: 'a + Copy> Extend<&'a T> for List<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> From<Vec<T>> for List<T> {
+    fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for List<T> {
+    fn from(array: [T; N]) -> Self {
+        array.into_iter().collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+//length first, so [[1], [2]] and [[1, 2]] hash differently
+    //no len field -> count it on the way through, then hash the elements
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.iter().count().hash(state);
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+
+/*
+IterMut can change elements, but not the shape of the list
+CursorMut walks forward like IterMut, but can also edit the links after it
+    insert_after, remove_next, split_after, splice_after
+    nodes are only moved between links, never reallocated
+
+only one pointer per node -> cursor can only go forward
+    and can only touch what comes *after* it
+starts on a "ghost" position in front of the head
+    so insert_after/remove_next on the ghost work on the head itself
+
+cursor holds two disjoint borrows of the same node
+    &mut elem for current(), &mut next link for everything else
+    splitting a &mut Node into its fields is fine for the borrow checker
+*/
+pub struct CursorMut<'a, T> {
+    //element the cursor is on, None while on the ghost
+    cur: Option<&'a mut T>,
+    //link right after the cursor, list.head while on the ghost
+        //only None for a moment inside move_next -> need to move the &'a mut out
+    next: Option<&'a mut Link<T>>,
+}
+
+impl<T> CursorMut<'_, T> {
+    fn link(&mut self) -> &mut Link<T> {
+        self.next.as_mut().unwrap()
+    }
+
+    //step onto the next node, false (and stay put) if there isn't one
+    pub fn move_next(&mut self) -> bool {
+        let link = self.next.take().unwrap();
+        if link.is_some() {
+            let node = link.as_deref_mut().unwrap();
+            self.cur = Some(&mut node.elem);
+            self.next = Some(&mut node.next);
+            true
+        } else {
+            self.next = Some(link);
+            false
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.cur.as_deref_mut()
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.link().as_deref_mut().map(|node| &mut node.elem)
+    }
+
+    //same as List::push, just on whatever link is after the cursor
+    pub fn insert_after(&mut self, elem: T) {
+        let link = self.link();
+        let new_node = Box::new(Node {
+            elem,
+            next: link.take(),
+        });
+        *link = Some(new_node);
+    }
+
+    //same as List::pop, just on whatever link is after the cursor
+    pub fn remove_next(&mut self) -> Option<T> {
+        let link = self.link();
+        link.take().map(|mut node| {
+            *link = node.next.take();
+            node.elem
+        })
+    }
+
+    //everything after the cursor moves into a new list -> just one link taken
+    pub fn split_after(&mut self) -> List<T> {
+        List { head: self.link().take() }
+    }
+
+    /*
+    put every node of other right after the cursor
+    need other's last node to hook our old next onto it
+        no tail pointer -> O(len of other) walk, but nodes are moved, not copied
+    */
+    pub fn splice_after(&mut self, mut other: List<T>) {
+        if let Some(mut other_head) = other.head.take() {
+            let link = self.link();
+            //is_some/unwrap instead of while let -> keeps borrow checker happy about 'last'
+            let mut last = &mut other_head;
+            while last.next.is_some() {
+                last = last.next.as_mut().unwrap();
+            }
+            last.next = link.take();
+            *link = Some(other_head);
+        }
+    }
+}
+
+
 #[cfg(test)] //indicates to only compile 'test' when running tests
 mod test {
     //made new module -> need to pull List explicitly to use it
@@ -301,6 +526,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::option_map_unit_fn)] //map kept on purpose, walks through the closure forms above
     fn peek() {
         let mut list = List::new();
         assert_eq!(list.peek(), None);
@@ -359,4 +585,237 @@ mod test {
         assert_eq!(iter.next(), Some(&mut 1));
     }
 
+    #[test]
+    fn cursor_mut() {
+        let mut list = List::new();
+        list.push(3); list.push(2); list.push(1);
+
+        let mut cursor = list.cursor_mut();
+        // Starts on the ghost in front of the head
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+
+        assert!(cursor.move_next());
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert!(cursor.move_next());
+        *cursor.current().unwrap() = 20;
+        assert!(cursor.move_next());
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(cursor.peek_next(), None);
+
+        // Can't walk off the end
+        assert!(!cursor.move_next());
+        assert_eq!(cursor.current(), Some(&mut 3));
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&20));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), None);
+
+        // Empty list: only the ghost
+        let mut empty: List<i32> = List::new();
+        let mut cursor = empty.cursor_mut();
+        assert!(!cursor.move_next());
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), None);
+    }
+
+    #[test]
+    fn cursor_insert_remove() {
+        let mut list = List::new();
+        list.push(3); list.push(1);
+
+        let mut cursor = list.cursor_mut();
+        // On the ghost these act on the head
+        cursor.insert_after(0);
+        assert_eq!(cursor.peek_next(), Some(&mut 0));
+        cursor.move_next();
+        cursor.move_next();
+        cursor.insert_after(2);
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_next();
+        cursor.move_next();
+        cursor.insert_after(4);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&4));
+        assert_eq!(iter.next(), None);
+
+        let mut cursor = list.cursor_mut();
+        assert_eq!(cursor.remove_next(), Some(0));
+        cursor.move_next();
+        assert_eq!(cursor.remove_next(), Some(2));
+        assert_eq!(cursor.remove_next(), Some(3));
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 4));
+        assert_eq!(cursor.remove_next(), None);
+
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), Some(4));
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn cursor_split_splice() {
+        let mut list = List::new();
+        list.push(5); list.push(4); list.push(3); list.push(2); list.push(1);
+
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        let mut back = cursor.split_after();
+        assert_eq!(cursor.peek_next(), None);
+
+        let mut iter = back.iter();
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&4));
+        assert_eq!(iter.next(), Some(&5));
+        assert_eq!(iter.next(), None);
+
+        // Splice it back in between 1 and 2
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.splice_after(back);
+        // Cursor doesn't move, spliced list follows it
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.peek_next(), Some(&mut 3));
+        // Splicing an empty list is a no-op
+        cursor.splice_after(List::new());
+        assert_eq!(cursor.peek_next(), Some(&mut 3));
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&4));
+        assert_eq!(iter.next(), Some(&5));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), None);
+
+        // Splitting on the ghost takes the whole list
+        let mut cursor = list.cursor_mut();
+        back = cursor.split_after();
+        assert_eq!(list.peek(), None);
+        assert_eq!(back.peek(), Some(&1));
+
+        // Splicing on the ghost puts the nodes at the front
+        let mut front = List::new();
+        front.push(0);
+        let mut cursor = back.cursor_mut();
+        cursor.splice_after(front);
+        assert_eq!(back.pop(), Some(0));
+        assert_eq!(back.pop(), Some(1));
+    }
+
+    #[test]
+    fn into_iterator_refs() {
+        let mut list = List::from([1, 2, 3]);
+
+        for elem in &mut list {
+            *elem *= 10;
+        }
+
+        let mut seen = Vec::new();
+        for elem in &list {
+            seen.push(*elem);
+        }
+        assert_eq!(seen, vec![30, 20, 10]);
+
+        let mut owned = Vec::new();
+        for elem in list {
+            owned.push(elem);
+        }
+        assert_eq!(owned, vec![30, 20, 10]);
+    }
+
+    #[test]
+    fn from_iter_extend() {
+        // Built like a stack -> last element ends up on top
+        let mut list: List<i32> = (1..=3).collect();
+        assert_eq!(list.peek(), Some(&3));
+
+        list.extend(vec![4, 5]);
+        list.extend(&[6]);
+        assert_eq!(list.pop(), Some(6));
+        assert_eq!(list.pop(), Some(5));
+        assert_eq!(list.pop(), Some(4));
+        assert_eq!(list.pop(), Some(3));
+
+        let mut from_vec = List::from(vec![1, 2, 3]);
+        let mut vec = vec![1, 2, 3];
+        assert_eq!(from_vec.pop(), vec.pop());
+        assert_eq!(List::from(vec![7, 8]), List::from([7, 8]));
+
+        let empty: List<i32> = List::default();
+        assert_eq!(empty.peek(), None);
+    }
+
+    #[test]
+    fn clone_keeps_order() {
+        let list = List::from(["a".to_string(), "b".to_string(), "c".to_string()]);
+        let copy = list.clone();
+        assert_eq!(list, copy);
+
+        let mut iter = copy.iter();
+        assert_eq!(iter.next().map(String::as_str), Some("c"));
+        assert_eq!(iter.next().map(String::as_str), Some("b"));
+        assert_eq!(iter.next().map(String::as_str), Some("a"));
+        assert_eq!(iter.next(), None);
+
+        // Long lists clone without recursion
+        let long: List<u32> = (0..100_000).collect();
+        let long_copy = long.clone();
+        assert_eq!(long_copy.peek(), Some(&99_999));
+    }
+
+    #[test]
+    fn debug() {
+        let list = List::from([1, 2, 3]);
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+
+        let empty: List<i32> = List::new();
+        assert_eq!(format!("{:?}", empty), "[]");
+    }
+
+    #[test]
+    fn eq_ord() {
+        use std::cmp::Ordering;
+
+        let a = List::from([1, 2, 3]);
+        let b = List::from([1, 2, 3]);
+        let c = List::from([0, 2, 3]);
+        let d = List::from([2, 3]);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        // Compared head first: a is [3, 2, 1], d is [3, 2]
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+        assert!(d < a);
+        assert!(c > d);
+        assert_eq!(a.partial_cmp(&c), Some(Ordering::Greater));
+
+        let nan = List::from([f64::NAN]);
+        assert_eq!(nan.partial_cmp(&nan), None);
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        assert!(set.insert(List::from([1, 2, 3])));
+        assert!(!set.insert(List::from(vec![1, 2, 3])));
+        assert!(set.insert(List::from([1, 2])));
+        assert!(set.contains(&List::from([1, 2])));
+
+        // Length is part of the hash
+        let mut nested = HashSet::new();
+        nested.insert(List::from([List::from([1]), List::from([2])]));
+        assert!(!nested.contains(&List::from([List::from([1, 2])])));
+    }
+
 }
\ No newline at end of file
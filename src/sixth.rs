@@ -0,0 +1,1511 @@
+/*
+production-quality doubly-linked list -> drop-in for std::collections::LinkedList
+
+Same idea as fourth.rs, but without Rc<RefCell> overhead
+    raw pointers both ways, list owns every node
+    length tracked so len() is O(1)
+
+Ownership keeps the conventions from second.rs
+    Link<T> is still an Option -> None means "no node", no null checks
+    nodes allocated with Box::new and freed with Box::from_raw
+    but never *held* as Box -> Box asserts uniqueness (see fifth.rs)
+    NonNull<T> is a *mut T that can't be null -> Option<NonNull<T>> is pointer-sized
+
+Variance: should a LinkedList<&'static str> be usable as a LinkedList<&'a str>?
+    yes for Box, Vec, std's LinkedList -> "covariant" in T
+    *mut T is invariant -> would make the list more annoying than std's
+    NonNull<T> is covariant, like *const T -> what we want
+    PhantomData<T> tells the compiler we own T's (drop check)
+
+Covariance is only sound because nothing lets you write a shorter-lived T
+into the list through a shared reference -> all mutation goes through &mut self
+*/
+
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+pub struct LinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    next: Link<T>,
+    prev: Link<T>,
+    elem: T,
+}
+
+impl<T> LinkedList<T> {
+    pub fn new() -> Self {
+        LinkedList { head: None, tail: None, len: 0, _boo: PhantomData }
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        unsafe {
+            //Box::new allocates, into_raw hands ownership over to the list
+            let new = NonNull::new_unchecked(Box::into_raw(Box::new(Node {
+                next: None,
+                prev: None,
+                elem,
+            })));
+            if let Some(old) = self.head {
+                (*old.as_ptr()).prev = Some(new);
+                (*new.as_ptr()).next = Some(old);
+            } else {
+                //empty list -> new node is the tail too
+                self.tail = Some(new);
+            }
+            self.head = Some(new);
+            self.len += 1;
+        }
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        unsafe {
+            let new = NonNull::new_unchecked(Box::into_raw(Box::new(Node {
+                next: None,
+                prev: None,
+                elem,
+            })));
+            if let Some(old) = self.tail {
+                (*old.as_ptr()).next = Some(new);
+                (*new.as_ptr()).prev = Some(old);
+            } else {
+                self.head = Some(new);
+            }
+            self.tail = Some(new);
+            self.len += 1;
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        unsafe {
+            self.head.map(|node| {
+                //take ownership back -> Box frees the node when it goes out of scope
+                let boxed_node = Box::from_raw(node.as_ptr());
+                let result = boxed_node.elem;
+
+                self.head = boxed_node.next;
+                if let Some(new) = self.head {
+                    (*new.as_ptr()).prev = None;
+                } else {
+                    //emptied the list -> tail would dangle
+                    self.tail = None;
+                }
+
+                self.len -= 1;
+                result
+            })
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        unsafe {
+            self.tail.map(|node| {
+                let boxed_node = Box::from_raw(node.as_ptr());
+                let result = boxed_node.elem;
+
+                self.tail = boxed_node.prev;
+                if let Some(new) = self.tail {
+                    (*new.as_ptr()).next = None;
+                } else {
+                    self.head = None;
+                }
+
+                self.len -= 1;
+                result
+            })
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.head.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.tail.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.tail.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|elem| elem == x)
+    }
+
+    //move every node of other onto our back -> O(1), just relinks the ends
+    pub fn append(&mut self, other: &mut Self) {
+        match (self.tail, other.head) {
+            (_, None) => {}
+            (None, Some(_)) => std::mem::swap(self, other),
+            (Some(tail), Some(other_head)) => unsafe {
+                (*tail.as_ptr()).next = Some(other_head);
+                (*other_head.as_ptr()).prev = Some(tail);
+                self.tail = other.tail.take();
+                self.len += std::mem::replace(&mut other.len, 0);
+                other.head = None;
+            },
+        }
+    }
+
+    /*
+    split at index -> self keeps [0, at), returns [at, len)
+    walk from whichever end is closer, then cut one pair of links
+    panics if at > len, same as std
+    */
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len;
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+        if at == 0 {
+            return std::mem::take(self);
+        }
+        if at == len {
+            return Self::new();
+        }
+
+        unsafe {
+            //find the node that becomes the new tail of self (index at - 1)
+            let split_node = if at - 1 <= len - 1 - (at - 1) {
+                let mut node = self.head.unwrap();
+                for _ in 0..at - 1 {
+                    node = (*node.as_ptr()).next.unwrap();
+                }
+                node
+            } else {
+                let mut node = self.tail.unwrap();
+                for _ in 0..len - at {
+                    node = (*node.as_ptr()).prev.unwrap();
+                }
+                node
+            };
+
+            let second_head = (*split_node.as_ptr()).next.take().unwrap();
+            (*second_head.as_ptr()).prev = None;
+
+            let second = LinkedList {
+                head: Some(second_head),
+                tail: self.tail,
+                len: len - at,
+                _boo: PhantomData,
+            };
+            self.tail = Some(split_node);
+            self.len = at;
+            second
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { head: self.head, tail: self.tail, len: self.len, _boo: PhantomData }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { head: self.head, tail: self.tail, len: self.len, _boo: PhantomData }
+    }
+
+    //cursor starts on the ghost -> first move_next lands on the head
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut { list: self, cur: None, index: None }
+    }
+}
+
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        //pop until empty -> frees nodes one by one, no recursion
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for LinkedList<T> {
+    fn clone(&self) -> Self {
+        let mut new_list = Self::new();
+        for item in self {
+            new_list.push_back(item.clone());
+        }
+        new_list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+//std also lets you extend from references for Copy types
+impl<'a, T: 'a + Copy> Extend<&'a T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+//comparisons are element-wise, in order -> delegate to the iterator methods
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+impl<T: PartialOrd> PartialOrd for LinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for LinkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+//hash the length first -> [[1], [2]] and [[1, 2]] don't collide
+impl<T: Hash> Hash for LinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+/*
+raw pointers are neither Send nor Sync -> compiler opts us out
+but the list owns its T's exactly like Box<T> does
+    safe to send if T is Send, safe to share if T is Sync
+*/
+unsafe impl<T: Send> Send for LinkedList<T> {}
+unsafe impl<T: Sync> Sync for LinkedList<T> {}
+
+//Iter only hands out &T -> sending it is sending a &T, which needs T: Sync (like std's Iter)
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+unsafe impl<T: Send> Send for CursorMut<'_, T> {}
+unsafe impl<T: Sync> Sync for CursorMut<'_, T> {}
+
+/*
+iterators hold both ends and a count
+    next() walks head forward, next_back() walks tail backward
+    len hits 0 when they meet -> stop, even though the pointers cross
+*/
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+pub struct IntoIter<T> {
+    list: LinkedList<T>,
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.head.map(|node| unsafe {
+                self.len -= 1;
+                self.head = (*node.as_ptr()).next;
+                &(*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.tail.map(|node| unsafe {
+                self.len -= 1;
+                self.tail = (*node.as_ptr()).prev;
+                &(*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+//Iter is just shared pointers -> can clone without T: Clone
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.head.map(|node| unsafe {
+                self.len -= 1;
+                self.head = (*node.as_ptr()).next;
+                &mut (*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.tail.map(|node| unsafe {
+                self.len -= 1;
+                self.tail = (*node.as_ptr()).prev;
+                &mut (*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.list.len
+    }
+}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+/*
+Iter/IterMut can only look at elements -> can't change the shape of the list
+a cursor sits *on* an element and can edit around it: insert, remove, split, splice
+    everything is O(1) since we're already holding the node pointer
+
+"ghost" element between tail and head -> cursor can point at nothing
+    move_next from the tail goes to the ghost, from the ghost to the head
+    move_prev the same backwards -> list behaves like a ring
+    on the ghost: current() is None, peek_next() is the head, peek_prev() the tail
+
+index tracked so callers know where they are -> None on the ghost
+cursor holds &mut LinkedList -> no one else can touch the list while it's alive
+*/
+pub struct CursorMut<'a, T> {
+    list: &'a mut LinkedList<T>,
+    cur: Link<T>,
+    index: Option<usize>,
+}
+
+impl<T> CursorMut<'_, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn move_next(&mut self) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                // We're on a real element, go to its next (back)
+                self.cur = (*cur.as_ptr()).next;
+                if self.cur.is_some() {
+                    *self.index.as_mut().unwrap() += 1;
+                } else {
+                    // We just walked to the ghost, no more index
+                    self.index = None;
+                }
+            }
+        } else if !self.list.is_empty() {
+            // We're at the ghost, and there is a real front, so move to it!
+            self.cur = self.list.head;
+            self.index = Some(0)
+        } else {
+            // We're at the ghost, but that's the only element... do nothing.
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let Some(cur) = self.cur {
+            unsafe {
+                // We're on a real element, go to its previous (front)
+                self.cur = (*cur.as_ptr()).prev;
+                if self.cur.is_some() {
+                    *self.index.as_mut().unwrap() -= 1;
+                } else {
+                    // We just walked to the ghost, no more index
+                    self.index = None;
+                }
+            }
+        } else if !self.list.is_empty() {
+            // We're at the ghost, and there is a real back, so move to it!
+            self.cur = self.list.tail;
+            self.index = Some(self.list.len - 1)
+        } else {
+            // We're at the ghost, but that's the only element... do nothing.
+        }
+    }
+
+    //references borrow the cursor -> can't move the cursor while holding one
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.cur.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = if let Some(cur) = self.cur {
+                (*cur.as_ptr()).next
+            } else {
+                // Ghost case, try to use the list's front
+                self.list.head
+            };
+
+            next.map(|node| &mut (*node.as_ptr()).elem)
+        }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = if let Some(cur) = self.cur {
+                (*cur.as_ptr()).prev
+            } else {
+                // Ghost case, try to use the list's back
+                self.list.tail
+            };
+
+            prev.map(|node| &mut (*node.as_ptr()).elem)
+        }
+    }
+
+    //single-element splices -> on the ghost these push onto the back / front
+    pub fn insert_before(&mut self, elem: T) {
+        let mut single = LinkedList::new();
+        single.push_back(elem);
+        self.splice_before(single);
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        let mut single = LinkedList::new();
+        single.push_back(elem);
+        self.splice_after(single);
+    }
+
+    //unlink current node, cursor moves on to the next one (ghost if it was the tail)
+    pub fn remove_current(&mut self) -> Option<T> {
+        let cur = self.cur?;
+        unsafe {
+            let boxed_node = Box::from_raw(cur.as_ptr());
+            let prev = boxed_node.prev;
+            let next = boxed_node.next;
+
+            if let Some(prev) = prev {
+                (*prev.as_ptr()).next = next;
+            } else {
+                self.list.head = next;
+            }
+            if let Some(next) = next {
+                (*next.as_ptr()).prev = prev;
+            } else {
+                self.list.tail = prev;
+            }
+
+            self.list.len -= 1;
+            // Same index now refers to the node after us
+            self.cur = next;
+            if next.is_none() {
+                self.index = None;
+            }
+
+            Some(boxed_node.elem)
+        }
+    }
+
+    /*
+    split_before: everything before current becomes a new list
+        list = [1, 2, A, 3, 4] with cursor on A
+        returns [1, 2], list becomes [A, 3, 4], cursor now at index 0
+    on the ghost the ghost's "before" is the whole list -> take it all
+    */
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        if let Some(cur) = self.cur {
+            // We are pointing at a real element, so the list is non-empty.
+            unsafe {
+                // Current state
+                let old_len = self.list.len;
+                let old_idx = self.index.unwrap();
+                let prev = (*cur.as_ptr()).prev;
+
+                // What self will become
+                let new_len = old_len - old_idx;
+                let new_front = self.cur;
+                let new_idx = Some(0);
+
+                // What the output will become
+                let output_len = old_len - new_len;
+                let output_front = if prev.is_some() { self.list.head } else { None };
+                let output_back = prev;
+
+                // Break the links between cur and prev
+                if let Some(prev) = prev {
+                    (*cur.as_ptr()).prev = None;
+                    (*prev.as_ptr()).next = None;
+                }
+
+                // Produce the result:
+                self.list.len = new_len;
+                self.list.head = new_front;
+                self.index = new_idx;
+
+                LinkedList {
+                    head: output_front,
+                    tail: output_back,
+                    len: output_len,
+                    _boo: PhantomData,
+                }
+            }
+        } else {
+            // We're at the ghost, just replace our list with an empty one.
+            // No other state needs to be changed.
+            std::mem::take(self.list)
+        }
+    }
+
+    //mirror image: everything after current, cursor keeps its index
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        if let Some(cur) = self.cur {
+            // We are pointing at a real element, so the list is non-empty.
+            unsafe {
+                // Current state
+                let old_len = self.list.len;
+                let old_idx = self.index.unwrap();
+                let next = (*cur.as_ptr()).next;
+
+                // What self will become
+                let new_len = old_idx + 1;
+                let new_back = self.cur;
+
+                // What the output will become
+                let output_len = old_len - new_len;
+                let output_front = next;
+                let output_back = if next.is_some() { self.list.tail } else { None };
+
+                // Break the links between cur and next
+                if let Some(next) = next {
+                    (*cur.as_ptr()).next = None;
+                    (*next.as_ptr()).prev = None;
+                }
+
+                // Produce the result:
+                self.list.len = new_len;
+                self.list.tail = new_back;
+
+                LinkedList {
+                    head: output_front,
+                    tail: output_back,
+                    len: output_len,
+                    _boo: PhantomData,
+                }
+            }
+        } else {
+            // We're at the ghost, just replace our list with an empty one.
+            // No other state needs to be changed.
+            std::mem::take(self.list)
+        }
+    }
+
+    /*
+    splice: stitch a whole list in next to the cursor, O(1)
+        splice_before on the ghost -> append to the back
+        splice_after on the ghost -> prepend to the front
+    input's nodes move over, input is left empty and dropped
+    */
+    pub fn splice_before(&mut self, mut input: LinkedList<T>) {
+        if input.is_empty() {
+            // Input is empty, do nothing.
+            return;
+        }
+
+        if let Some(cur) = self.cur {
+            unsafe {
+                // Take input's pointers, input stays empty so its Drop frees nothing
+                let in_front = input.head.take().unwrap();
+                let in_back = input.tail.take().unwrap();
+                let in_len = std::mem::replace(&mut input.len, 0);
+
+                if let Some(prev) = (*cur.as_ptr()).prev {
+                    // General Case, no boundaries, just internal fixups
+                    (*prev.as_ptr()).next = Some(in_front);
+                    (*in_front.as_ptr()).prev = Some(prev);
+                } else {
+                    // No prev, we're appending to the front
+                    self.list.head = Some(in_front);
+                }
+                (*cur.as_ptr()).prev = Some(in_back);
+                (*in_back.as_ptr()).next = Some(cur);
+
+                // Index moves forward by input length
+                *self.index.as_mut().unwrap() += in_len;
+                self.list.len += in_len;
+            }
+        } else {
+            // We're on the ghost, "before" is the back of the list
+            self.list.append(&mut input);
+        }
+    }
+
+    pub fn splice_after(&mut self, mut input: LinkedList<T>) {
+        if input.is_empty() {
+            // Input is empty, do nothing.
+            return;
+        }
+
+        if let Some(cur) = self.cur {
+            unsafe {
+                let in_front = input.head.take().unwrap();
+                let in_back = input.tail.take().unwrap();
+                let in_len = std::mem::replace(&mut input.len, 0);
+
+                if let Some(next) = (*cur.as_ptr()).next {
+                    // General Case, no boundaries, just internal fixups
+                    (*next.as_ptr()).prev = Some(in_back);
+                    (*in_back.as_ptr()).next = Some(next);
+                } else {
+                    // No next, we're appending to the back
+                    self.list.tail = Some(in_back);
+                }
+                (*cur.as_ptr()).next = Some(in_front);
+                (*in_front.as_ptr()).prev = Some(cur);
+
+                // Index doesn't change
+                self.list.len += in_len;
+            }
+        } else {
+            // We're on the ghost, "after" is the front of the list
+            input.append(self.list);
+            std::mem::swap(self.list, &mut input);
+        }
+    }
+}
+
+/*
+compile-time checks -> never called, only need to type-check
+    covariance: shorter lifetimes accepted where longer ones were given
+    Send/Sync: list and iterators are thread-safe when T is
+the negative check has to fail to compile -> lives in the doctest below
+*/
+/// ```compile_fail
+/// use lists::sixth::Iter;
+/// use std::cell::Cell;
+///
+/// // Cell is Send but not Sync -> an Iter over it must not cross threads
+/// fn is_send<T: Send>() {}
+/// is_send::<Iter<Cell<i32>>>();
+/// ```
+#[allow(dead_code)]
+fn assert_properties() {
+    use std::cell::Cell;
+
+    fn is_send<T: Send>() {}
+    fn is_sync<T: Sync>() {}
+
+    is_send::<LinkedList<i32>>();
+    is_sync::<LinkedList<i32>>();
+
+    is_send::<IntoIter<i32>>();
+    is_sync::<IntoIter<i32>>();
+
+    is_send::<Iter<i32>>();
+    is_sync::<Iter<i32>>();
+
+    is_send::<IterMut<i32>>();
+    is_sync::<IterMut<i32>>();
+
+    //Send but not Sync -> owning or mutating it from another thread is fine, sharing it isn't
+    is_send::<LinkedList<Cell<i32>>>();
+    is_send::<IntoIter<Cell<i32>>>();
+    is_send::<IterMut<Cell<i32>>>();
+
+    is_send::<CursorMut<i32>>();
+    is_sync::<CursorMut<i32>>();
+
+    fn linked_list_covariant<'a, T>(x: LinkedList<&'static T>) -> LinkedList<&'a T> { x }
+    fn iter_covariant<'i, 'a, T>(x: Iter<'i, &'static T>) -> Iter<'i, &'a T> { x }
+    fn into_iter_covariant<'a, T>(x: IntoIter<&'static T>) -> IntoIter<&'a T> { x }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LinkedList;
+    use std::collections::VecDeque;
+
+    fn generate_test() -> LinkedList<i32> {
+        list_from(&[0, 1, 2, 3, 4, 5, 6])
+    }
+
+    fn list_from<T: Clone>(v: &[T]) -> LinkedList<T> {
+        v.iter().map(|x| (*x).clone()).collect()
+    }
+
+    #[test]
+    fn test_basic_front() {
+        let mut list = LinkedList::new();
+
+        // Try to break an empty list
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+
+        // Try to break a one item list
+        list.push_front(10);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+
+        // Mess around
+        list.push_front(10);
+        assert_eq!(list.len(), 1);
+        list.push_front(20);
+        assert_eq!(list.len(), 2);
+        list.push_front(30);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.pop_front(), Some(30));
+        assert_eq!(list.len(), 2);
+        list.push_front(40);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.pop_front(), Some(40));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.pop_front(), Some(20));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.len(), 0);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_basic() {
+        let mut m = LinkedList::new();
+        assert_eq!(m.pop_front(), None);
+        assert_eq!(m.pop_back(), None);
+        assert_eq!(m.pop_front(), None);
+        m.push_front(1);
+        assert_eq!(m.pop_front(), Some(1));
+        m.push_back(2);
+        m.push_back(3);
+        assert_eq!(m.len(), 2);
+        assert_eq!(m.pop_front(), Some(2));
+        assert_eq!(m.pop_front(), Some(3));
+        assert_eq!(m.len(), 0);
+        assert_eq!(m.pop_front(), None);
+        m.push_back(1);
+        m.push_back(3);
+        m.push_back(5);
+        m.push_back(7);
+        assert_eq!(m.pop_front(), Some(1));
+
+        let mut n = LinkedList::new();
+        n.push_front(2);
+        n.push_front(3);
+        {
+            assert_eq!(n.front().unwrap(), &3);
+            let x = n.front_mut().unwrap();
+            assert_eq!(*x, 3);
+            *x = 0;
+        }
+        {
+            assert_eq!(n.back().unwrap(), &2);
+            let y = n.back_mut().unwrap();
+            assert_eq!(*y, 2);
+            *y = 1;
+        }
+        assert_eq!(n.pop_front(), Some(0));
+        assert_eq!(n.pop_front(), Some(1));
+    }
+
+    #[test]
+    fn test_iterator() {
+        let m = generate_test();
+        for (i, elt) in m.iter().enumerate() {
+            assert_eq!(i as i32, *elt);
+        }
+        let mut n = LinkedList::new();
+        assert_eq!(n.iter().next(), None);
+        n.push_front(4);
+        let mut it = n.iter();
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next().unwrap(), &4);
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_iterator_double_end() {
+        let mut n = LinkedList::new();
+        assert_eq!(n.iter().next(), None);
+        n.push_front(4);
+        n.push_front(5);
+        n.push_front(6);
+        let mut it = n.iter();
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        assert_eq!(it.next().unwrap(), &6);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(it.next_back().unwrap(), &4);
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next_back().unwrap(), &5);
+        assert_eq!(it.next_back(), None);
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_rev_iter() {
+        let m = generate_test();
+        for (i, elt) in m.iter().rev().enumerate() {
+            assert_eq!(6 - i as i32, *elt);
+        }
+        let mut n = LinkedList::new();
+        assert_eq!(n.iter().next_back(), None);
+        n.push_front(4);
+        let mut it = n.iter().rev();
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(it.next().unwrap(), &4);
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn test_mut_iter() {
+        let mut m = generate_test();
+        let mut len = m.len();
+        for (i, elt) in m.iter_mut().enumerate() {
+            assert_eq!(i as i32, *elt);
+            len -= 1;
+        }
+        assert_eq!(len, 0);
+        let mut n = LinkedList::new();
+        assert!(n.iter_mut().next().is_none());
+        n.push_front(4);
+        n.push_back(5);
+        let mut it = n.iter_mut();
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert!(it.next().is_some());
+        assert!(it.next().is_some());
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_iterator_mut_double_end() {
+        let mut n = LinkedList::new();
+        assert!(n.iter_mut().next_back().is_none());
+        n.push_front(4);
+        n.push_front(5);
+        n.push_front(6);
+        let mut it = n.iter_mut();
+        assert_eq!(it.size_hint(), (3, Some(3)));
+        assert_eq!(*it.next().unwrap(), 6);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(*it.next_back().unwrap(), 4);
+        assert_eq!(it.size_hint(), (1, Some(1)));
+        assert_eq!(*it.next_back().unwrap(), 5);
+        assert!(it.next_back().is_none());
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_into_iter_double_end() {
+        let mut it = generate_test().into_iter();
+        assert_eq!(it.len(), 7);
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(it.next_back(), Some(6));
+        assert_eq!(it.len(), 5);
+        assert_eq!(it.collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut n: LinkedList<u8> = list_from(&[]);
+        let mut m = list_from(&[]);
+        assert!(n == m);
+        n.push_front(1);
+        assert!(n != m);
+        m.push_back(1);
+        assert!(n == m);
+
+        let n = list_from(&[2, 3, 4]);
+        let m = list_from(&[1, 2, 3]);
+        assert!(n != m);
+    }
+
+    #[test]
+    fn test_ord() {
+        let n = list_from(&[]);
+        let m = list_from(&[1, 2, 3]);
+        assert!(n < m);
+        assert!(m > n);
+        assert!(n <= n);
+        assert!(n >= n);
+    }
+
+    #[test]
+    fn test_ord_nan() {
+        use std::cmp::Ordering::Greater;
+
+        //NaN compares as neither less, greater, nor equal -> partial_cmp gives None
+        let nan = f64::NAN;
+        let n = list_from(&[nan]);
+        let m = list_from(&[nan]);
+        assert_eq!(n.partial_cmp(&m), None);
+
+        let one = list_from(&[1.0f64]);
+        assert_eq!(n.partial_cmp(&one), None);
+
+        let u = list_from(&[1.0f64, 2.0, nan]);
+        let v = list_from(&[1.0f64, 2.0, 3.0]);
+        assert_eq!(u.partial_cmp(&v), None);
+
+        // Comparison stops at the first difference, before reaching NaN
+        let s = list_from(&[1.0f64, 2.0, 4.0, nan]);
+        let t = list_from(&[1.0f64, 2.0, 3.0, 2.0]);
+        assert_eq!(s.partial_cmp(&t), Some(Greater));
+        assert!(s > one);
+        assert!(s >= one);
+    }
+
+    #[test]
+    fn test_debug() {
+        let list: LinkedList<i32> = (0..10).collect();
+        assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+
+        let list: LinkedList<&str> = ["just", "one", "test", "more"].iter().copied().collect();
+        assert_eq!(format!("{:?}", list), r#"["just", "one", "test", "more"]"#);
+    }
+
+    #[test]
+    fn test_hashmap() {
+        // Check that HashMap works with this as a key
+
+        let list1: LinkedList<i32> = (0..10).collect();
+        let list2: LinkedList<i32> = (1..11).collect();
+        let mut map = std::collections::HashMap::new();
+
+        assert_eq!(map.insert(list1.clone(), "list1"), None);
+        assert_eq!(map.insert(list2.clone(), "list2"), None);
+
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get(&list1), Some(&"list1"));
+        assert_eq!(map.get(&list2), Some(&"list2"));
+
+        assert_eq!(map.remove(&list1), Some("list1"));
+        assert_eq!(map.remove(&list2), Some("list2"));
+
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_clone_extend_contains() {
+        let mut list = list_from(&[1, 2, 3]);
+        let copy = list.clone();
+        list.extend([4, 5]);
+        list.extend(&[6]);
+
+        assert_eq!(copy.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+        assert!(list.contains(&5));
+        assert!(!copy.contains(&5));
+
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        assert_eq!(list.back(), None);
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = list_from(&[1, 2]);
+        let mut b = list_from(&[3, 4, 5]);
+        a.append(&mut b);
+        assert_eq!(a.len(), 5);
+        assert!(b.is_empty());
+        assert_eq!(a.iter().rev().copied().collect::<Vec<_>>(), vec![5, 4, 3, 2, 1]);
+
+        // Appending onto or from an empty list
+        let mut empty = LinkedList::new();
+        empty.append(&mut a);
+        assert!(a.is_empty());
+        assert_eq!(empty.len(), 5);
+        empty.append(&mut a);
+        assert_eq!(empty.len(), 5);
+
+        // The emptied list is still usable
+        a.push_back(9);
+        assert_eq!(a.front(), Some(&9));
+        assert_eq!(a.back(), Some(&9));
+    }
+
+    #[test]
+    fn test_split_off() {
+        for at in 0..=7 {
+            let mut list = generate_test();
+            let back = list.split_off(at);
+            assert_eq!(list.len(), at);
+            assert_eq!(back.len(), 7 - at);
+            assert_eq!(list.iter().copied().collect::<Vec<_>>(), (0..at as i32).collect::<Vec<_>>());
+            assert_eq!(back.iter().copied().collect::<Vec<_>>(), (at as i32..7).collect::<Vec<_>>());
+            assert_eq!(list.iter().rev().count(), at);
+            assert_eq!(back.iter().rev().count(), 7 - at);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_out_of_bounds() {
+        let mut list = generate_test();
+        list.split_off(8);
+    }
+
+    #[test]
+    fn test_drop_counts() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct DropCounter(Rc<Cell<usize>>);
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        {
+            let mut list = LinkedList::new();
+            for _ in 0..10 {
+                list.push_back(DropCounter(drops.clone()));
+            }
+            let tail = list.split_off(4);
+            drop(list.pop_front());
+            assert_eq!(drops.get(), 1);
+            let mut iter = tail.into_iter();
+            drop(iter.next_back());
+            assert_eq!(drops.get(), 2);
+        }
+        assert_eq!(drops.get(), 10);
+    }
+
+    fn check_links<T: Eq + std::fmt::Debug>(list: &LinkedList<T>) {
+        let from_front: Vec<_> = list.iter().collect();
+        let from_back: Vec<_> = list.iter().rev().collect();
+        let re_reved: Vec<_> = from_back.into_iter().rev().collect();
+
+        assert_eq!(from_front, re_reved);
+        assert_eq!(from_front.len(), list.len());
+    }
+
+    #[test]
+    fn test_cursor_move_peek() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        m.extend([1, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.peek_next(), Some(&mut 2));
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.index(), Some(0));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 6));
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        assert_eq!(cursor.peek_next(), Some(&mut 3));
+        assert_eq!(cursor.peek_prev(), Some(&mut 1));
+        assert_eq!(cursor.index(), Some(1));
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 6));
+        assert_eq!(cursor.peek_next(), None);
+        assert_eq!(cursor.peek_prev(), Some(&mut 5));
+        assert_eq!(cursor.index(), Some(5));
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 6));
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 5));
+        assert_eq!(cursor.peek_next(), Some(&mut 6));
+        assert_eq!(cursor.peek_prev(), Some(&mut 4));
+        assert_eq!(cursor.index(), Some(4));
+
+        // Moving on an empty list stays on the ghost
+        let mut empty: LinkedList<u32> = LinkedList::new();
+        let mut cursor = empty.cursor_mut();
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+    }
+
+    #[test]
+    fn test_cursor_mut_insert() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        m.extend([1, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.splice_before(Some(7).into_iter().collect());
+        cursor.splice_after(Some(8).into_iter().collect());
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[7, 1, 8, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_prev();
+        cursor.splice_before(Some(9).into_iter().collect());
+        cursor.splice_after(Some(10).into_iter().collect());
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[10, 7, 1, 8, 2, 3, 4, 5, 6, 9]);
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), None);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(7));
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(9));
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(10));
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 8, 2, 3, 4, 5, 6]);
+
+        let mut m: LinkedList<u32> = LinkedList::new();
+        m.extend([1, 8, 2, 3, 4, 5, 6]);
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        let mut p: LinkedList<u32> = LinkedList::new();
+        p.extend([100, 101, 102, 103]);
+        let mut q: LinkedList<u32> = LinkedList::new();
+        q.extend([200, 201, 202, 203]);
+        cursor.splice_after(p);
+        cursor.splice_before(q);
+        check_links(&m);
+        assert_eq!(
+            m.iter().cloned().collect::<Vec<_>>(),
+            &[200, 201, 202, 203, 1, 100, 101, 102, 103, 8, 2, 3, 4, 5, 6]
+        );
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_prev();
+        let tmp = cursor.split_before();
+        assert_eq!(m.into_iter().collect::<Vec<_>>(), &[]);
+        let mut m = tmp;
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        let tmp = cursor.split_after();
+        assert_eq!(tmp.into_iter().collect::<Vec<_>>(), &[102, 103, 8, 2, 3, 4, 5, 6]);
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[200, 201, 202, 203, 1, 100, 101]);
+    }
+
+    #[test]
+    fn test_cursor_insert_remove_single() {
+        let mut m: LinkedList<u32> = LinkedList::new();
+        let mut cursor = m.cursor_mut();
+
+        // On the ghost: insert_before pushes back, insert_after pushes front
+        cursor.insert_before(2);
+        cursor.insert_after(1);
+        cursor.insert_before(3);
+        assert_eq!(cursor.index(), None);
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 2, 3]);
+
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        cursor.insert_before(10);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.insert_after(20);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.peek_next(), Some(&mut 20));
+        check_links(&m);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 10, 2, 20, 3]);
+
+        // Removing the tail puts the cursor on the ghost
+        let mut cursor = m.cursor_mut();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.peek_prev(), Some(&mut 20));
+
+        // Removing the head moves the cursor onto the new head
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 10));
+        check_links(&m);
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.front(), Some(&10));
+        assert_eq!(m.back(), Some(&20));
+    }
+
+    #[test]
+    fn test_cursor_split_edges() {
+        let mut m: LinkedList<u32> = (0..5).collect();
+        let mut cursor = m.cursor_mut();
+        cursor.move_next();
+
+        // Splitting before the head gives nothing
+        let front = cursor.split_before();
+        assert!(front.is_empty());
+        assert_eq!(cursor.index(), Some(0));
+
+        // Splitting after the tail gives nothing
+        cursor.move_prev();
+        cursor.move_prev();
+        let back = cursor.split_after();
+        assert!(back.is_empty());
+        assert_eq!(cursor.index(), Some(4));
+
+        // Split in the middle, both halves stay consistent
+        cursor.move_prev();
+        cursor.move_prev();
+        let back = cursor.split_after();
+        assert_eq!(cursor.index(), Some(2));
+        let front = cursor.split_before();
+        assert_eq!(cursor.index(), Some(0));
+        check_links(&m);
+        check_links(&front);
+        check_links(&back);
+        assert_eq!(front.into_iter().collect::<Vec<_>>(), &[0, 1]);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[2]);
+        assert_eq!(back.into_iter().collect::<Vec<_>>(), &[3, 4]);
+    }
+
+    /*
+    stress test against VecDeque as a reference model
+    tiny xorshift generator -> deterministic, no external crates
+    */
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    fn assert_same(list: &LinkedList<u64>, model: &VecDeque<u64>) {
+        assert_eq!(list.len(), model.len());
+        assert_eq!(list.is_empty(), model.is_empty());
+        assert_eq!(list.front(), model.front());
+        assert_eq!(list.back(), model.back());
+        assert!(list.iter().eq(model.iter()));
+        assert!(list.iter().rev().eq(model.iter().rev()));
+    }
+
+    #[test]
+    fn stress_against_vecdeque() {
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+        let mut list = LinkedList::new();
+        let mut model = VecDeque::new();
+
+        for _ in 0..10_000 {
+            match rng.below(11) {
+                0 | 1 => {
+                    let x = rng.next();
+                    list.push_front(x);
+                    model.push_front(x);
+                }
+                2 | 3 => {
+                    let x = rng.next();
+                    list.push_back(x);
+                    model.push_back(x);
+                }
+                4 => assert_eq!(list.pop_front(), model.pop_front()),
+                5 => assert_eq!(list.pop_back(), model.pop_back()),
+                6 => {
+                    if let (Some(a), Some(b)) = (list.front_mut(), model.front_mut()) {
+                        *a = a.wrapping_add(1);
+                        *b = b.wrapping_add(1);
+                    }
+                }
+                7 => {
+                    let at = rng.below(model.len() + 1);
+                    let mut back = list.split_off(at);
+                    let mut model_back = model.split_off(at);
+                    assert_same(&back, &model_back);
+                    back.push_back(rng.next());
+                    model_back.push_back(*back.back().unwrap());
+                    list.append(&mut back);
+                    model.append(&mut model_back);
+                }
+                8 => {
+                    for (a, b) in list.iter_mut().zip(model.iter_mut()) {
+                        *a ^= 1;
+                        *b ^= 1;
+                    }
+                }
+                9 => {
+                    let x = rng.next() % 4;
+                    assert_eq!(list.contains(&x), model.contains(&x));
+                }
+                _ => {
+                    if rng.below(20) == 0 {
+                        list.clear();
+                        model.clear();
+                    }
+                }
+            }
+            assert_same(&list, &model);
+        }
+
+        assert!(list.clone().into_iter().eq(model.clone()));
+        assert!(list.into_iter().rev().eq(model.into_iter().rev()));
+    }
+}
@@ -0,0 +1,323 @@
+/*
+shared interfaces -> pick a list at compile time, write the code once
+
+Stack<T>: mutable, single-owner LIFO (first::List, second::List, unrolled::Stack)
+PersistentStack<T>: immutable, shared LIFO (third::List, third::sync::List)
+    push/pop don't make sense when tails are shared
+    prepend/tail hand back new lists instead
+
+iter() returns a different struct for every list
+    associated type with a lifetime parameter (generic associated type)
+    'where Self: 'a' -> the iterator can't outlive the list it borrows
+
+the trait methods just forward to the inherent ones
+    inherent methods win method lookup, so list.push(x) still works without the trait in scope
+*/
+
+use crate::{first, second, third, unrolled};
+
+pub trait Stack<T> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn push(&mut self, elem: T);
+    fn pop(&mut self) -> Option<T>;
+    fn peek(&self) -> Option<&T>;
+    fn peek_mut(&mut self) -> Option<&mut T>;
+    fn iter(&self) -> Self::Iter<'_>;
+
+    //none of the stacks store a length -> walk it, O(n)
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.peek().is_none()
+    }
+}
+
+pub trait PersistentStack<T>: Sized {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn prepend(&self, elem: T) -> Self;
+    fn tail(&self) -> Self;
+    fn head(&self) -> Option<&T>;
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+impl<T> Stack<T> for first::List<T> {
+    type Iter<'a> = first::Iter<'a, T> where T: 'a;
+
+    fn push(&mut self, elem: T) {
+        first::List::push(self, elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        first::List::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        first::List::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        first::List::peek_mut(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        first::List::iter(self)
+    }
+}
+
+impl<T> Stack<T> for second::List<T> {
+    type Iter<'a> = second::Iter<'a, T> where T: 'a;
+
+    fn push(&mut self, elem: T) {
+        second::List::push(self, elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        second::List::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        second::List::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        second::List::peek_mut(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        second::List::iter(self)
+    }
+}
+
+impl<T, const N: usize> Stack<T> for unrolled::Stack<T, N> {
+    type Iter<'a> = unrolled::Iter<'a, T, N> where T: 'a;
+
+    fn push(&mut self, elem: T) {
+        unrolled::Stack::push(self, elem)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        unrolled::Stack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        unrolled::Stack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        unrolled::Stack::peek_mut(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        unrolled::Stack::iter(self)
+    }
+}
+
+impl<T, P: third::PointerFamily> PersistentStack<T> for third::GenericList<T, P> {
+    type Iter<'a> = third::Iter<'a, T, P> where T: 'a, P: 'a;
+
+    fn prepend(&self, elem: T) -> Self {
+        third::GenericList::prepend(self, elem)
+    }
+
+    fn tail(&self) -> Self {
+        third::GenericList::tail(self)
+    }
+
+    fn head(&self) -> Option<&T> {
+        third::GenericList::head(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        third::GenericList::iter(self)
+    }
+}
+
+/*
+one behavioural suite per trait, stamped out for every implementor
+    stack_tests!(module_name, Type) -> module of #[test]s that only use the trait
+elements are i32 -> the suites only check behaviour, conformance.rs covers element types
+*/
+#[cfg(test)]
+macro_rules! stack_tests {
+    ($name:ident, $list:ty) => {
+        mod $name {
+            use crate::stack::Stack;
+
+            fn new() -> $list {
+                <$list>::new()
+            }
+
+            #[test]
+            fn basics() {
+                let mut list = new();
+                assert_eq!(Stack::pop(&mut list), None);
+                assert!(Stack::is_empty(&list));
+                assert_eq!(Stack::len(&list), 0);
+
+                Stack::push(&mut list, 1);
+                Stack::push(&mut list, 2);
+                Stack::push(&mut list, 3);
+                assert_eq!(Stack::len(&list), 3);
+                assert!(!Stack::is_empty(&list));
+
+                assert_eq!(Stack::pop(&mut list), Some(3));
+                assert_eq!(Stack::pop(&mut list), Some(2));
+
+                Stack::push(&mut list, 4);
+                assert_eq!(Stack::pop(&mut list), Some(4));
+                assert_eq!(Stack::pop(&mut list), Some(1));
+                assert_eq!(Stack::pop(&mut list), None);
+                assert!(Stack::is_empty(&list));
+            }
+
+            #[test]
+            fn peek() {
+                let mut list = new();
+                assert_eq!(Stack::peek(&list), None);
+                assert_eq!(Stack::peek_mut(&mut list), None);
+
+                Stack::push(&mut list, 1);
+                Stack::push(&mut list, 2);
+                assert_eq!(Stack::peek(&list), Some(&2));
+
+                if let Some(top) = Stack::peek_mut(&mut list) {
+                    *top = 42;
+                }
+                assert_eq!(Stack::peek(&list), Some(&42));
+                assert_eq!(Stack::pop(&mut list), Some(42));
+                assert_eq!(Stack::peek(&list), Some(&1));
+            }
+
+            #[test]
+            fn iter() {
+                let mut list = new();
+                Stack::push(&mut list, 1);
+                Stack::push(&mut list, 2);
+                Stack::push(&mut list, 3);
+
+                let mut iter = Stack::iter(&list);
+                assert_eq!(iter.next(), Some(&3));
+                assert_eq!(iter.next(), Some(&2));
+                assert_eq!(iter.next(), Some(&1));
+                assert_eq!(iter.next(), None);
+
+                // Iterating doesn't consume anything
+                assert_eq!(Stack::len(&list), 3);
+            }
+
+            #[test]
+            fn generic_caller() {
+                // Code written against the trait, not the list
+                fn drain<S: Stack<i32>>(stack: &mut S) -> Vec<i32> {
+                    let mut out = Vec::new();
+                    while let Some(elem) = stack.pop() {
+                        out.push(elem);
+                    }
+                    out
+                }
+
+                let mut list = new();
+                for i in 0..5 {
+                    Stack::push(&mut list, i);
+                }
+                assert_eq!(drain(&mut list), vec![4, 3, 2, 1, 0]);
+                assert!(Stack::is_empty(&list));
+            }
+
+            #[test]
+            fn long_list_drop() {
+                let mut list = new();
+                for i in 0..100_000 {
+                    Stack::push(&mut list, i);
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+macro_rules! persistent_stack_tests {
+    ($name:ident, $list:ty) => {
+        mod $name {
+            use crate::stack::PersistentStack;
+
+            fn new() -> $list {
+                <$list>::new()
+            }
+
+            #[test]
+            fn basics() {
+                let list = new();
+                assert_eq!(PersistentStack::head(&list), None);
+
+                let list = list.prepend(1).prepend(2).prepend(3);
+                assert_eq!(PersistentStack::head(&list), Some(&3));
+
+                let list = PersistentStack::tail(&list);
+                assert_eq!(PersistentStack::head(&list), Some(&2));
+
+                let list = PersistentStack::tail(&list);
+                let list = PersistentStack::tail(&list);
+                assert_eq!(PersistentStack::head(&list), None);
+
+                // Tail of an empty list is empty
+                let list = PersistentStack::tail(&list);
+                assert_eq!(PersistentStack::head(&list), None);
+            }
+
+            #[test]
+            fn old_versions_survive() {
+                let base = new().prepend(1).prepend(2);
+                let left = base.prepend(3);
+                let right = base.prepend(4);
+
+                assert_eq!(PersistentStack::iter(&left).copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+                assert_eq!(PersistentStack::iter(&right).copied().collect::<Vec<_>>(), vec![4, 2, 1]);
+                assert_eq!(PersistentStack::iter(&base).copied().collect::<Vec<_>>(), vec![2, 1]);
+            }
+
+            #[test]
+            fn generic_caller() {
+                fn sum<S: PersistentStack<i32>>(stack: &S) -> i32 {
+                    PersistentStack::iter(stack).sum()
+                }
+
+                let mut list = new();
+                for i in 1..=4 {
+                    list = list.prepend(i);
+                }
+                assert_eq!(sum(&list), 10);
+                assert_eq!(sum(&PersistentStack::tail(&list)), 6);
+            }
+
+            #[test]
+            fn long_list_drop() {
+                let mut list = new();
+                for i in 0..100_000 {
+                    list = list.prepend(i);
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+stack_tests!(first_list, crate::first::List<i32>);
+#[cfg(test)]
+stack_tests!(second_list, crate::second::List<i32>);
+#[cfg(test)]
+stack_tests!(unrolled_stack, crate::unrolled::Stack<i32, 4>);
+#[cfg(test)]
+persistent_stack_tests!(third_list, crate::third::List<i32>);
+#[cfg(test)]
+persistent_stack_tests!(third_sync_list, crate::third::sync::List<i32>);
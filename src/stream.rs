@@ -0,0 +1,387 @@
+/*
+lazy stream -> third::List, but each tail is only computed when someone looks at it
+    infinite sequences are fine, nobody ever asks for the whole thing
+    same sharing as third::List -> cloning a stream is one Rc bump
+
+every link is a thunk: "the rest of the stream, once computed"
+    OnceCell holds the result -> computed at most once (memoized)
+        two clones of a stream see the same nodes, neither recomputes
+    the closure that computes it sits next to the cell until it runs
+        taken out and run on first force, then gone -> captured state is freed
+
+closures are Box<dyn FnOnce> + 'static
+    so anything a stream captures (elements, functions, other streams) has to be 'static
+    lifetimes on Stream<'a, T> would work but every signature gets noisier
+
+forcing a thunk from inside its own closure (stream defined in terms of itself) panics
+    the closure has already been taken -> nothing left to run
+    same if the closure panicked once: the thunk stays unevaluated for good
+*/
+
+use std::cell::{Cell, OnceCell};
+use std::rc::Rc;
+
+//None is the empty stream, known without forcing anything -> no allocation either
+pub struct Stream<T> {
+    link: Option<Rc<Thunk<T>>>,
+}
+
+type Step<T> = Option<(T, Stream<T>)>;
+type Init<T> = Box<dyn FnOnce() -> Step<T>>;
+
+struct Thunk<T> {
+    value: OnceCell<Option<Node<T>>>,
+    init: Cell<Option<Init<T>>>,
+}
+
+struct Node<T> {
+    elem: T,
+    next: Stream<T>,
+}
+
+impl<T> Thunk<T> {
+    //run the closure the first time, hand back the stored node after that
+    fn force(&self) -> Option<&Node<T>> {
+        self.value
+            .get_or_init(|| {
+                let init = self.init.take().expect("stream forced while it was being computed");
+                init().map(|(elem, next)| Node { elem, next })
+            })
+            .as_ref()
+    }
+}
+
+impl<T> Stream<T> {
+    pub fn new() -> Self {
+        Stream { link: None }
+    }
+
+    //already evaluated -> no closure needed, value goes straight into the cell
+    pub fn cons(elem: T, tail: Stream<T>) -> Self {
+        let thunk = Thunk {
+            value: OnceCell::from(Some(Node { elem, next: tail })),
+            init: Cell::new(None),
+        };
+        Stream { link: Some(Rc::new(thunk)) }
+    }
+
+    fn force(&self) -> Option<&Node<T>> {
+        self.link.as_ref()?.force()
+    }
+
+    //the accessors below force the first link -> may run a closure
+    pub fn head(&self) -> Option<&T> {
+        self.force().map(|node| &node.elem)
+    }
+
+    pub fn tail(&self) -> Self {
+        self.force().map_or_else(Stream::new, |node| node.next.clone())
+    }
+
+    pub fn uncons(&self) -> Option<(&T, Self)> {
+        self.force().map(|node| (&node.elem, node.next.clone()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.force().is_none()
+    }
+
+    //only forces as far as the iterator is driven
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next: Some(self) }
+    }
+}
+
+impl<T: 'static> Stream<T> {
+    //stream whose first link is computed later by f
+    fn lazy<F>(f: F) -> Self
+    where
+        F: FnOnce() -> Step<T> + 'static,
+    {
+        let thunk = Thunk { value: OnceCell::new(), init: Cell::new(Some(Box::new(f))) };
+        Stream { link: Some(Rc::new(thunk)) }
+    }
+
+    //calls f once per element forced, ends at the first None
+        //f moves into the next link's closure each step -> FnMut state carries over
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut() -> Option<T> + 'static,
+    {
+        Self::lazy(move || f().map(|elem| (elem, Self::from_fn(f))))
+    }
+
+    //seed, f(seed), f(f(seed)), ... -> never ends
+        //previous element is needed to make the next one, node keeps its own copy -> T: Clone
+    pub fn iterate<F>(seed: T, f: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(&T) -> T + 'static,
+    {
+        Self::cons(seed.clone(), Self::iterate_after(seed, f))
+    }
+
+    fn iterate_after<F>(prev: T, mut f: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(&T) -> T + 'static,
+    {
+        Self::lazy(move || {
+            let elem = f(&prev);
+            Some((elem.clone(), Self::iterate_after(elem, f)))
+        })
+    }
+
+    /*
+    combinators build new lazy streams over self
+        nothing is forced until the result is -> map over an infinite stream is fine
+        results are new nodes, so elements get cloned out of the source (like third::take)
+    */
+    pub fn take(&self, n: usize) -> Self
+    where
+        T: Clone,
+    {
+        if n == 0 {
+            return Self::new();
+        }
+        let source = self.clone();
+        Self::lazy(move || {
+            let (elem, rest) = source.uncons()?;
+            Some((elem.clone(), rest.take(n - 1)))
+        })
+    }
+
+    pub fn map<U, F>(&self, mut f: F) -> Stream<U>
+    where
+        U: 'static,
+        F: FnMut(&T) -> U + 'static,
+    {
+        let source = self.clone();
+        Stream::lazy(move || {
+            let (elem, rest) = source.uncons()?;
+            Some((f(elem), rest.map(f)))
+        })
+    }
+
+    //forcing a link skips ahead to the next match
+        //an infinite stream with no more matches never finishes forcing
+    pub fn filter<F>(&self, mut f: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(&T) -> bool + 'static,
+    {
+        let mut source = self.clone();
+        Self::lazy(move || loop {
+            let (elem, rest) = source.uncons()?;
+            if f(elem) {
+                return Some((elem.clone(), rest.filter(f)));
+            }
+            source = rest;
+        })
+    }
+
+    //as long as the shorter stream, like Iterator::zip
+    pub fn zip<U>(&self, other: &Stream<U>) -> Stream<(T, U)>
+    where
+        T: Clone,
+        U: Clone + 'static,
+    {
+        let (left, right) = (self.clone(), other.clone());
+        Stream::lazy(move || {
+            let (a, left) = left.uncons()?;
+            let (b, right) = right.uncons()?;
+            Some(((a.clone(), b.clone()), left.zip(&right)))
+        })
+    }
+}
+
+//O(1), shares every node and every unevaluated thunk
+impl<T> Clone for Stream<T> {
+    fn clone(&self) -> Self {
+        Stream { link: self.link.clone() }
+    }
+}
+
+impl<T> Default for Stream<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//same shape as third::Iter, but holds the stream, not the node
+    //holding a node would mean forcing one link ahead of what's been asked for
+pub struct Iter<'a, T> {
+    next: Option<&'a Stream<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?.force()?;
+        self.next = Some(&node.next);
+        Some(&node.elem)
+    }
+}
+
+//same iterative drop as third::List -> unwrap thunks we're the last owner of
+    //an evaluated thunk gives up its node, and its next link is the next to unwrap
+    //an unevaluated one just drops its closure (and whatever the closure captured)
+impl<T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        let mut link = self.link.take();
+        while let Some(thunk) = link {
+            link = match Rc::try_unwrap(thunk) {
+                Ok(thunk) => {
+                    let node = thunk.value.into_inner().flatten();
+                    node.and_then(|mut node| node.next.link.take())
+                }
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stream;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn to_vec<T: Clone>(stream: &Stream<T>) -> Vec<T> {
+        stream.iter().cloned().collect()
+    }
+
+    //0, 1, 2, ... counting how many elements have been computed
+    fn counted_naturals(calls: &Rc<Cell<usize>>) -> Stream<usize> {
+        let calls = calls.clone();
+        let mut n = 0;
+        Stream::from_fn(move || {
+            calls.set(calls.get() + 1);
+            n += 1;
+            Some(n - 1)
+        })
+    }
+
+    #[test]
+    fn basics() {
+        let stream: Stream<i32> = Stream::new();
+        assert_eq!(stream.head(), None);
+        assert!(stream.is_empty());
+        assert!(stream.tail().is_empty());
+
+        let stream = Stream::cons(1, Stream::cons(2, Stream::new()));
+        assert_eq!(stream.head(), Some(&1));
+        assert_eq!(stream.tail().head(), Some(&2));
+        assert!(stream.tail().tail().is_empty());
+
+        let (head, tail) = stream.uncons().unwrap();
+        assert_eq!((*head, tail.head()), (1, Some(&2)));
+    }
+
+    #[test]
+    fn lazy_and_memoized() {
+        let calls = Rc::new(Cell::new(0));
+        let stream = counted_naturals(&calls);
+        assert_eq!(calls.get(), 0);
+
+        // Only what's looked at gets computed
+        assert_eq!(stream.iter().take(3).copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(calls.get(), 3);
+
+        // Second walk (and a clone) reuse the computed nodes
+        let copy = stream.clone();
+        assert_eq!(copy.iter().take(3).copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(calls.get(), 3);
+
+        assert_eq!(stream.tail().tail().tail().head(), Some(&3));
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn from_fn_ends() {
+        let mut left = 3;
+        let stream = Stream::from_fn(move || {
+            left -= 1;
+            (left >= 0).then_some(left)
+        });
+        assert_eq!(to_vec(&stream), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn infinite_combinators() {
+        let naturals = Stream::iterate(0u64, |n| n + 1);
+        let evens = naturals.filter(|n| n % 2 == 0);
+        let squares = naturals.map(|n| n * n);
+
+        assert_eq!(to_vec(&evens.take(4)), vec![0, 2, 4, 6]);
+        assert_eq!(to_vec(&squares.take(4)), vec![0, 1, 4, 9]);
+        assert_eq!(
+            to_vec(&naturals.zip(&squares).take(3)),
+            vec![(0, 0), (1, 1), (2, 4)]
+        );
+        assert!(naturals.take(0).is_empty());
+    }
+
+    #[test]
+    fn combinators_stay_lazy() {
+        let calls = Rc::new(Cell::new(0));
+        let naturals = counted_naturals(&calls);
+
+        let odds = naturals.map(|n| n * 10).filter(|n| n % 20 != 0).take(2);
+        assert_eq!(calls.get(), 0);
+
+        assert_eq!(odds.head(), Some(&10));
+        assert_eq!(calls.get(), 2);
+        assert_eq!(to_vec(&odds), vec![10, 30]);
+        // take stops asking once it has two
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn fibonacci() {
+        let fib = Stream::iterate((0u64, 1u64), |&(a, b)| (b, a + b)).map(|&(a, _)| a);
+        assert_eq!(to_vec(&fib.take(10)), vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn unevaluated_drop_frees_captures() {
+        let tracker = Rc::new(());
+        let captured = tracker.clone();
+        let stream = Stream::from_fn(move || Some(Rc::strong_count(&captured)));
+        assert_eq!(Rc::strong_count(&tracker), 2);
+        drop(stream);
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn long_stream_drop() {
+        // Evaluated chain of a million nodes -> drop must not recurse
+        let stream = Stream::iterate(0u64, |n| n + 1);
+        assert_eq!(stream.iter().nth(1_000_000), Some(&1_000_000));
+        drop(stream);
+
+        // Same through a combinator, with a shared tail that survives
+        let source = Stream::iterate(0u64, |n| n + 1);
+        let mapped = source.map(|n| n * 2);
+        assert_eq!(mapped.iter().nth(100_000), Some(&200_000));
+        let rest = mapped.iter().nth(10).copied();
+        let tail = mapped.tail();
+        drop(mapped);
+        assert_eq!(tail.head(), Some(&2));
+        assert_eq!(rest, Some(20));
+    }
+
+    #[test]
+    #[should_panic(expected = "being computed")]
+    fn self_reference_panics() {
+        let cell: Rc<Cell<Option<Stream<i32>>>> = Rc::new(Cell::new(None));
+        let inner = cell.clone();
+        let stream = Stream::from_fn(move || {
+            let me = inner.take().unwrap();
+            me.head().copied()
+        });
+        cell.set(Some(stream.clone()));
+        stream.head();
+    }
+}
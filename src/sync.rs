@@ -0,0 +1,448 @@
+/*
+blocking hand-off between threads, built on this crate's lists instead of std's channels
+    BlockingStack: second::List inside -> last pushed comes out first
+    BlockingQueue: fifth::List (tail pointer queue) inside -> first pushed comes out first
+
+one Blocking core, generic over the list it keeps (the Buffer trait)
+    list + element count + closed flag behind one Mutex
+    two Condvars: not_empty wakes poppers, not_full wakes pushers
+    capacity bounds the count -> push waits while full, pop waits while empty
+
+close(): no more pushes
+    every waiter is woken, pushers get their value back in the error
+    poppers still get whatever is left, the error only comes once it's empty
+*/
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::{fifth, second};
+
+//what Blocking needs from a list -> put one in, take the next one out
+pub trait Buffer<T> {
+    fn put(&mut self, elem: T);
+    fn take(&mut self) -> Option<T>;
+}
+
+impl<T> Buffer<T> for second::List<T> {
+    fn put(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn take(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+impl<T> Buffer<T> for fifth::List<T> {
+    fn put(&mut self, elem: T) {
+        self.push(elem)
+    }
+
+    fn take(&mut self) -> Option<T> {
+        self.pop()
+    }
+}
+
+pub struct Blocking<T, B> {
+    state: Mutex<State<B>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    //B holds the T's -> marker just ties T to the type, fn() keeps auto traits up to B
+    _elem: PhantomData<fn() -> T>,
+}
+
+pub type BlockingStack<T> = Blocking<T, second::List<T>>;
+pub type BlockingQueue<T> = Blocking<T, fifth::List<T>>;
+
+struct State<B> {
+    buf: B,
+    //the lists don't count their elements -> keep count here for the capacity check
+    len: usize,
+    closed: bool,
+}
+
+/*
+errors -> push ones hand the value back, like std's SendError
+    plain push/pop can only fail because of close
+    try_ ones can also be full/empty, _timeout ones can also time out
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PushError<T>(pub T);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryPushError<T> {
+    Full(T),
+    Closed(T),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushTimeoutError<T> {
+    Timeout(T),
+    Closed(T),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryPopError {
+    Empty,
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopTimeoutError {
+    Timeout,
+    Closed,
+}
+
+impl<T> TryPushError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            TryPushError::Full(elem) | TryPushError::Closed(elem) => elem,
+        }
+    }
+}
+
+impl<T> PushTimeoutError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            PushTimeoutError::Timeout(elem) | PushTimeoutError::Closed(elem) => elem,
+        }
+    }
+}
+
+impl<T> fmt::Display for PushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("pushing on a closed container")
+    }
+}
+
+impl<T> fmt::Display for TryPushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryPushError::Full(_) => f.write_str("pushing on a full container"),
+            TryPushError::Closed(_) => f.write_str("pushing on a closed container"),
+        }
+    }
+}
+
+impl<T> fmt::Display for PushTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushTimeoutError::Timeout(_) => f.write_str("timed out pushing on a full container"),
+            PushTimeoutError::Closed(_) => f.write_str("pushing on a closed container"),
+        }
+    }
+}
+
+impl fmt::Display for PopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("popping from a closed and empty container")
+    }
+}
+
+impl fmt::Display for TryPopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryPopError::Empty => f.write_str("popping from an empty container"),
+            TryPopError::Closed => f.write_str("popping from a closed and empty container"),
+        }
+    }
+}
+
+impl fmt::Display for PopTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopTimeoutError::Timeout => f.write_str("timed out popping from an empty container"),
+            PopTimeoutError::Closed => f.write_str("popping from a closed and empty container"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for PushError<T> {}
+impl<T: fmt::Debug> Error for TryPushError<T> {}
+impl<T: fmt::Debug> Error for PushTimeoutError<T> {}
+impl Error for PopError {}
+impl Error for TryPopError {}
+impl Error for PopTimeoutError {}
+
+impl<T, B: Buffer<T> + Default> Blocking<T, B> {
+    //capacity 0 would mean nothing can ever be pushed -> refuse
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "blocking containers need a capacity of at least one");
+        Blocking {
+            state: Mutex::new(State { buf: B::default(), len: 0, closed: false }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity,
+            _elem: PhantomData,
+        }
+    }
+}
+
+impl<T, B: Buffer<T>> Blocking<T, B> {
+    //a thread panicking while holding the lock can't leave the state half-changed
+        //(every change below is a single step) -> just keep going on poison
+    fn lock(&self) -> MutexGuard<'_, State<B>> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn put(&self, mut state: MutexGuard<'_, State<B>>, elem: T) {
+        state.buf.put(elem);
+        state.len += 1;
+        drop(state);
+        self.not_empty.notify_one();
+    }
+
+    fn take(&self, mut state: MutexGuard<'_, State<B>>) -> T {
+        let elem = state.buf.take().unwrap();
+        state.len -= 1;
+        drop(state);
+        self.not_full.notify_one();
+        elem
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    //snapshots -> other threads may change them right after
+    pub fn len(&self) -> usize {
+        self.lock().len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    //waits while full
+    pub fn push(&self, elem: T) -> Result<(), PushError<T>> {
+        let state = self.lock();
+        let state = self
+            .not_full
+            .wait_while(state, |s| s.len == self.capacity && !s.closed)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.closed {
+            return Err(PushError(elem));
+        }
+        self.put(state, elem);
+        Ok(())
+    }
+
+    pub fn try_push(&self, elem: T) -> Result<(), TryPushError<T>> {
+        let state = self.lock();
+        if state.closed {
+            Err(TryPushError::Closed(elem))
+        } else if state.len == self.capacity {
+            Err(TryPushError::Full(elem))
+        } else {
+            self.put(state, elem);
+            Ok(())
+        }
+    }
+
+    //wait_timeout_while handles spurious wakeups and counts down what's left of the timeout
+    pub fn push_timeout(&self, elem: T, timeout: Duration) -> Result<(), PushTimeoutError<T>> {
+        let state = self.lock();
+        let (state, _) = self
+            .not_full
+            .wait_timeout_while(state, timeout, |s| s.len == self.capacity && !s.closed)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.closed {
+            Err(PushTimeoutError::Closed(elem))
+        } else if state.len == self.capacity {
+            Err(PushTimeoutError::Timeout(elem))
+        } else {
+            self.put(state, elem);
+            Ok(())
+        }
+    }
+
+    //waits while empty, fails once closed and drained
+    pub fn pop(&self) -> Result<T, PopError> {
+        let state = self.lock();
+        let state = self
+            .not_empty
+            .wait_while(state, |s| s.len == 0 && !s.closed)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.len == 0 {
+            return Err(PopError);
+        }
+        Ok(self.take(state))
+    }
+
+    pub fn try_pop(&self) -> Result<T, TryPopError> {
+        let state = self.lock();
+        match (state.len, state.closed) {
+            (0, true) => Err(TryPopError::Closed),
+            (0, false) => Err(TryPopError::Empty),
+            _ => Ok(self.take(state)),
+        }
+    }
+
+    pub fn pop_timeout(&self, timeout: Duration) -> Result<T, PopTimeoutError> {
+        let state = self.lock();
+        let (state, _) = self
+            .not_empty
+            .wait_timeout_while(state, timeout, |s| s.len == 0 && !s.closed)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match (state.len, state.closed) {
+            (0, true) => Err(PopTimeoutError::Closed),
+            (0, false) => Err(PopTimeoutError::Timeout),
+            _ => Ok(self.take(state)),
+        }
+    }
+
+    //wakes everyone -> their wait conditions all check closed
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        BlockingQueue, BlockingStack, PopError, PopTimeoutError, PushError, PushTimeoutError,
+        TryPopError, TryPushError,
+    };
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    const SHORT: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn order() {
+        let stack = BlockingStack::new(4);
+        let queue = BlockingQueue::new(4);
+        for i in 1..=3 {
+            stack.push(i).unwrap();
+            queue.push(i).unwrap();
+        }
+        assert_eq!(stack.len(), 3);
+        assert_eq!((stack.pop(), queue.pop()), (Ok(3), Ok(1)));
+        assert_eq!((stack.pop(), queue.pop()), (Ok(2), Ok(2)));
+        assert_eq!((stack.pop(), queue.pop()), (Ok(1), Ok(3)));
+        assert!(stack.is_empty() && queue.is_empty());
+    }
+
+    #[test]
+    fn try_and_timeout() {
+        let queue = BlockingQueue::new(2);
+        assert_eq!(queue.try_pop(), Err(TryPopError::Empty));
+        assert_eq!(queue.pop_timeout(SHORT), Err(PopTimeoutError::Timeout));
+
+        queue.try_push(1).unwrap();
+        queue.push_timeout(2, SHORT).unwrap();
+        assert_eq!(queue.try_push(3), Err(TryPushError::Full(3)));
+
+        let start = Instant::now();
+        assert_eq!(queue.push_timeout(3, SHORT), Err(PushTimeoutError::Timeout(3)));
+        assert!(start.elapsed() >= SHORT);
+
+        assert_eq!(queue.try_pop(), Ok(1));
+        assert_eq!(queue.pop_timeout(SHORT), Ok(2));
+        assert_eq!(TryPushError::Full(7).into_inner(), 7);
+    }
+
+    #[test]
+    fn close() {
+        let stack = BlockingStack::new(4);
+        stack.push(1).unwrap();
+        stack.push(2).unwrap();
+        stack.close();
+        assert!(stack.is_closed());
+
+        // No more pushes, value comes back
+        assert_eq!(stack.push(3), Err(PushError(3)));
+        assert_eq!(stack.try_push(3), Err(TryPushError::Closed(3)));
+        assert_eq!(stack.push_timeout(3, SHORT), Err(PushTimeoutError::Closed(3)));
+
+        // What's there can still be popped
+        assert_eq!(stack.pop(), Ok(2));
+        assert_eq!(stack.try_pop(), Ok(1));
+        assert_eq!(stack.pop(), Err(PopError));
+        assert_eq!(stack.try_pop(), Err(TryPopError::Closed));
+        assert_eq!(stack.pop_timeout(SHORT), Err(PopTimeoutError::Closed));
+    }
+
+    #[test]
+    fn close_wakes_waiters() {
+        let full = Arc::new(BlockingQueue::new(1));
+        let empty: Arc<BlockingStack<i32>> = Arc::new(BlockingStack::new(1));
+        full.push(0).unwrap();
+
+        let pusher = {
+            let full = full.clone();
+            thread::spawn(move || full.push(1))
+        };
+        let popper = {
+            let empty = empty.clone();
+            thread::spawn(move || empty.pop())
+        };
+
+        // Give both a chance to block first -> close must wake them either way
+        thread::sleep(SHORT);
+        full.close();
+        empty.close();
+        assert_eq!(pusher.join().unwrap(), Err(PushError(1)));
+        assert_eq!(popper.join().unwrap(), Err(PopError));
+    }
+
+    #[test]
+    fn hand_off() {
+        // Capacity 2 with 1000 items -> producers block on full, consumers on empty
+        const ITEMS: usize = 1000;
+        let queue = Arc::new(BlockingQueue::new(2));
+
+        let producers: Vec<_> = (0..2)
+            .map(|p| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    for i in 0..ITEMS / 2 {
+                        queue.push(p * ITEMS / 2 + i).unwrap();
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..2)
+            .map(|_| {
+                let queue = queue.clone();
+                thread::spawn(move || {
+                    let mut got = Vec::new();
+                    while let Ok(elem) = queue.pop() {
+                        got.push(elem);
+                    }
+                    got
+                })
+            })
+            .collect();
+
+        for handle in producers {
+            handle.join().unwrap();
+        }
+        queue.close();
+
+        let mut all: Vec<usize> = consumers.into_iter().flat_map(|h| h.join().unwrap()).collect();
+        all.sort();
+        assert_eq!(all, (0..ITEMS).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity")]
+    fn zero_capacity() {
+        BlockingStack::<i32>::new(0);
+    }
+}
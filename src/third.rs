@@ -1,5 +1,3 @@
-use std::mem;
-
 /*
 move from single ownership to shared ownership
 make persistent immutable singly-linked list
@@ -22,22 +20,103 @@ Reference-counted GC -> use 'Rc', like Box but can duplicate it
         Can't get data out or mutate
 */
 
+use std::ops::Deref;
 use std::rc::Rc;
+use std::sync::Arc;
+
+/*
+Rc or Arc? -> let the caller pick, write the list once
+    List is generic over a "pointer family": a type that names a smart pointer
+    P::Pointer<U> is Rc<U> for RcFamily, Arc<U> for ArcFamily
+    associated type with its own generic parameter (generic associated type)
+        can't just write List<T, Rc> -> Rc on its own isn't a type, Rc<U> is
+
+GenericList<T, P> is the list itself, named lists are aliases
+    third::List<T> is still the Rc list
+    third::sync::List<T> is the Arc one -> Send + Sync when T is
+aliases instead of a default type parameter (List<T, P = RcFamily>)
+    defaults aren't used for inference -> List::new() wouldn't know which family to pick
+    through an alias the family is fixed, List::new() just works
+*/
+pub trait PointerFamily {
+    type Pointer<U>: Deref<Target = U> + Clone;
+
+    fn new<U>(value: U) -> Self::Pointer<U>;
+    //hand back the value if this is the last pointer, otherwise the pointer itself
+    fn try_unwrap<U>(ptr: Self::Pointer<U>) -> Result<U, Self::Pointer<U>>;
+    //same allocation? -> identity, not equality of the values inside
+    fn ptr_eq<U>(a: &Self::Pointer<U>, b: &Self::Pointer<U>) -> bool;
+    //&mut to the value only if this is the one and only pointer to it
+    fn get_mut<U>(ptr: &mut Self::Pointer<U>) -> Option<&mut U>;
+}
+
+pub struct RcFamily;
+pub struct ArcFamily;
+
+impl PointerFamily for RcFamily {
+    type Pointer<U> = Rc<U>;
+
+    fn new<U>(value: U) -> Rc<U> {
+        Rc::new(value)
+    }
+
+    fn try_unwrap<U>(ptr: Rc<U>) -> Result<U, Rc<U>> {
+        Rc::try_unwrap(ptr)
+    }
+
+    fn ptr_eq<U>(a: &Rc<U>, b: &Rc<U>) -> bool {
+        Rc::ptr_eq(a, b)
+    }
+
+    fn get_mut<U>(ptr: &mut Rc<U>) -> Option<&mut U> {
+        Rc::get_mut(ptr)
+    }
+}
+
+impl PointerFamily for ArcFamily {
+    type Pointer<U> = Arc<U>;
+
+    fn new<U>(value: U) -> Arc<U> {
+        Arc::new(value)
+    }
+
+    fn try_unwrap<U>(ptr: Arc<U>) -> Result<U, Arc<U>> {
+        Arc::try_unwrap(ptr)
+    }
+
+    fn ptr_eq<U>(a: &Arc<U>, b: &Arc<U>) -> bool {
+        Arc::ptr_eq(a, b)
+    }
 
-pub struct List<T> {
-    head: Link<T>,
+    fn get_mut<U>(ptr: &mut Arc<U>) -> Option<&mut U> {
+        Arc::get_mut(ptr)
+    }
+}
+
+//repr(transparent): same layout as the Link inside -> see split_first
+#[repr(transparent)]
+pub struct GenericList<T, P: PointerFamily> {
+    head: Link<T, P>,
 }
 
-struct Node<T> {
+pub type List<T> = GenericList<T, RcFamily>;
+
+/*
+every node remembers the length of the list starting at it
+    nodes never change after they're built -> the suffix never changes either
+    so the count can't go stale, and prepend just adds one to next's count
+*/
+struct Node<T, P: PointerFamily> {
     elem: T,
-    next: Link<T>,
+    len: usize,
+    next: Link<T, P>,
 }
 
-type Link<T> = Option<Rc<Node<T>>>;
+type Link<T, P> = Option<<P as PointerFamily>::Pointer<Node<T, P>>>;
 
-impl<T> List<T> {
+impl<T, P: PointerFamily> GenericList<T, P> {
     pub fn new() -> Self {
-        List { head: None }
+        GenericList { head: None }
     }
 
     //replace push with prepend for immutable list
@@ -47,16 +126,17 @@ impl<T> List<T> {
         //Never implicitly invoked -> used by Rc
     
     //don't need to mathc on head -> Option exposes Clone implementation
-    pub fn prepend(&self, elem: T) -> List<T> {
-        List { head: Some(Rc::new(Node {
-            elem: elem,
+    pub fn prepend(&self, elem: T) -> Self {
+        GenericList { head: Some(P::new(Node {
+            elem,
+            len: self.len() + 1,
             next: self.head.clone(),
         }))}
     }
 
     //replace pop with tail -> return whole list with first element removed
-    pub fn tail(&self) -> List<T> {
-        List { head: self.head.as_ref().and_then(|node| node.next.clone()) }
+    pub fn tail(&self) -> Self {
+        GenericList { head: self.head.as_ref().and_then(|node| node.next.clone()) }
     }
 
     //head provides reference to first element -> just peek from mutable list
@@ -64,17 +144,200 @@ impl<T> List<T> {
         self.head.as_ref().map(|node| &node.elem )
     }
     
-    pub fn iter(&self) -> Iter<'_, T> {
+    pub fn iter(&self) -> Iter<'_, T, P> {
         Iter { next: self.head.as_deref() }
     }
+
+    //read straight off the head node -> O(1)
+    pub fn len(&self) -> usize {
+        self.head.as_ref().map_or(0, |node| node.len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    //list with the first k elements dropped, None if there aren't k
+        //bounds check is O(1) thanks to len, the walk itself is still O(k)
+        //only the final link gets cloned -> one refcount bump, not k
+    pub fn nth_tail(&self, k: usize) -> Option<Self> {
+        if k > self.len() {
+            return None;
+        }
+        let mut link = &self.head;
+        for _ in 0..k {
+            link = &link.as_ref().unwrap().next;
+        }
+        Some(GenericList { head: link.clone() })
+    }
+
+    /*
+    how many trailing nodes do self and other physically share?
+        once two lists meet at a node, they share everything after it
+        -> line both up at the same remaining length, then walk in lockstep
+    compares node identity (ptr_eq), not element values
+        two lists built separately from equal values share nothing
+    */
+    pub fn common_suffix_len(&self, other: &Self) -> usize {
+        let mut a = self.suffix_link(other.len());
+        let mut b = other.suffix_link(self.len());
+        loop {
+            match (a, b) {
+                (Some(x), Some(y)) if P::ptr_eq(x, y) => return x.len,
+                (Some(x), Some(y)) => {
+                    a = x.next.as_ref();
+                    b = y.next.as_ref();
+                }
+                _ => return 0,
+            }
+        }
+    }
+
+    //node identities front to back, for code that measures sharing (history.rs)
+    pub(crate) fn node_addrs(&self) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.head.as_deref(), |node| node.next.as_deref())
+            .map(|node| node as *const Node<T, P> as usize)
+    }
+
+    //one node's allocation -> the node plus Rc/Arc's strong and weak counts
+    pub(crate) fn node_bytes() -> usize {
+        std::mem::size_of::<Node<T, P>>() + 2 * std::mem::size_of::<usize>()
+    }
+
+    //first link whose suffix is at most len long
+    fn suffix_link(&self, len: usize) -> Option<&P::Pointer<Node<T, P>>> {
+        let mut link = self.head.as_ref();
+        for _ in 0..self.len().saturating_sub(len) {
+            link = link.and_then(|node| node.next.as_ref());
+        }
+        link
+    }
+}
+
+/*
+persistent operations -> every one returns a new list, self is never touched
+
+sharing: a node can only be reused if everything after it is reused too
+    nodes are immutable, and next is part of the node
+    so only a *suffix* of an input can ever be shared
+        append: all of other is shared, self gets copied in front of it
+        skip: pure suffix -> shares everything, copies nothing
+        filter: everything after the last dropped element is shared
+        take/reverse/map/zip: new nodes (except when the answer is the input itself)
+copying needs T: Clone -> elements get cloned into the new nodes
+
+lists are built back to front: collect the prefix, then prepend in reverse
+    no recursion anywhere, so long lists are fine
+*/
+impl<T, P: PointerFamily> GenericList<T, P> {
+    //prefix elements (in order) prepended onto base
+    fn with_prefix(prefix: Vec<T>, base: Self) -> Self {
+        prefix.into_iter().rev().fold(base, |list, elem| list.prepend(elem))
+    }
+
+    pub fn append(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        if other.is_empty() {
+            return self.clone();
+        }
+        Self::with_prefix(self.iter().cloned().collect(), other.clone())
+    }
+
+    pub fn reverse(&self) -> Self
+    where
+        T: Clone,
+    {
+        if self.len() <= 1 {
+            return self.clone();
+        }
+        self.iter().fold(Self::new(), |list, elem| list.prepend(elem.clone()))
+    }
+
+    pub fn map<U, F>(&self, mut f: F) -> GenericList<U, P>
+    where
+        F: FnMut(&T) -> U,
+    {
+        GenericList::with_prefix(self.iter().map(&mut f).collect(), GenericList::new())
+    }
+
+    //f sees every element once, front to back
+    pub fn filter<F>(&self, mut f: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(&T) -> bool,
+    {
+        let keep: Vec<bool> = self.iter().map(&mut f).collect();
+        match keep.iter().rposition(|&kept| !kept) {
+            //nothing dropped -> the answer is self
+            None => self.clone(),
+            Some(last_dropped) => {
+                let prefix = self
+                    .iter()
+                    .zip(&keep)
+                    .take(last_dropped)
+                    .filter(|(_, &kept)| kept)
+                    .map(|(elem, _)| elem.clone())
+                    .collect();
+                let suffix = self.nth_tail(last_dropped + 1).unwrap();
+                Self::with_prefix(prefix, suffix)
+            }
+        }
+    }
+
+    pub fn take(&self, n: usize) -> Self
+    where
+        T: Clone,
+    {
+        if n >= self.len() {
+            return self.clone();
+        }
+        Self::with_prefix(self.iter().take(n).cloned().collect(), Self::new())
+    }
+
+    //past the end -> empty list, like Iterator::skip
+    pub fn skip(&self, n: usize) -> Self {
+        self.nth_tail(n).unwrap_or_default()
+    }
+
+    //as long as the shorter list, like Iterator::zip
+    pub fn zip<U>(&self, other: &GenericList<U, P>) -> GenericList<(T, U), P>
+    where
+        T: Clone,
+        U: Clone,
+    {
+        let pairs = self.iter().cloned().zip(other.iter().cloned()).collect();
+        GenericList::with_prefix(pairs, GenericList::new())
+    }
+
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &T) -> B,
+    {
+        self.iter().fold(init, f)
+    }
+}
+
+//cloning a list is just cloning the head pointer -> O(1), shares every node
+impl<T, P: PointerFamily> Clone for GenericList<T, P> {
+    fn clone(&self) -> Self {
+        GenericList { head: self.head.clone() }
+    }
+}
+
+impl<T, P: PointerFamily> Default for GenericList<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 //Iter is identical to structure of mutable list
-pub struct Iter<'a, T> {
-    next: Option<&'a Node<T>>,
+pub struct Iter<'a, T, P: PointerFamily = RcFamily> {
+    next: Option<&'a Node<T, P>>,
 }
 
-impl<'a, T> Iterator for Iter<'a, T> {
+impl<'a, T, P: PointerFamily> Iterator for Iter<'a, T, P> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -87,6 +350,8 @@ impl<'a, T> Iterator for Iter<'a, T> {
 
 
 //Can't implement IntoIter or IterMut -> only have shared access to elements
+    //...in general. If we hold the *only* pointer to a node, nobody else can see it
+    //so mutating or moving out of it is fine -> copy-on-write below
 
 //Have recursive descturo problem again -> can't mutate Node inside the Box
     //Rc only gives shared access
@@ -94,13 +359,144 @@ impl<'a, T> Iterator for Iter<'a, T> {
     //It would be find to move Node out of Rc
     //Then know when to stop, whenever we can't hoist ou the Node
     
-    //Rc has 'try_unwrap' method for this
+    //Rc has 'try_unwrap' method for this -> so does Arc, P::try_unwrap picks the right one
+
+/*
+pattern matching on a list -> functional style recursion
+    head() and tail() separately means tail() bumps a refcount every step
+    uncons: head and tail in one go, still one refcount bump (tail is an owned list)
+    split_first / view: borrow the tail instead -> no refcounts touched at all
+
+borrowed tail is a &GenericList pointing at a node's `next` field
+    a node's next is a Link, the list is a struct around a Link
+    repr(transparent) guarantees they have the same layout
+        so a &Link can be reinterpreted as a &GenericList
+    only ever a shared reference -> never dropped, never written through
+*/
+pub enum ListRef<'a, T, P: PointerFamily = RcFamily> {
+    Nil,
+    Cons(&'a T, &'a GenericList<T, P>),
+}
+
+//only holds references -> always Copy, whatever T is (derive would demand T: Copy)
+impl<T, P: PointerFamily> Clone for ListRef<'_, T, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, P: PointerFamily> Copy for ListRef<'_, T, P> {}
+
+impl<T, P: PointerFamily> GenericList<T, P> {
+    fn from_link(link: &Link<T, P>) -> &Self {
+        unsafe { &*(link as *const Link<T, P> as *const Self) }
+    }
+
+    //like slice::split_first -> first element and the rest, both borrowed
+    pub fn split_first(&self) -> Option<(&T, &Self)> {
+        self.head
+            .as_ref()
+            .map(|node| (&node.elem, Self::from_link(&node.next)))
+    }
+
+    pub fn view(&self) -> ListRef<'_, T, P> {
+        match self.split_first() {
+            Some((head, tail)) => ListRef::Cons(head, tail),
+            None => ListRef::Nil,
+        }
+    }
+
+    //owned tail -> can outlive self, costs one refcount bump
+    pub fn uncons(&self) -> Option<(&T, Self)> {
+        self.head
+            .as_ref()
+            .map(|node| (&node.elem, GenericList { head: node.next.clone() }))
+    }
+}
+
+/*
+copy-on-write: shared nodes are read-only, but unique ones can be changed in place
+    Rc::get_mut -> Some(&mut) only when the strong count is 1 (and no Weak)
+    Rc::try_unwrap -> the node itself, same condition (already used by Drop)
+
+"uniquely owned prefix": walk from the head while every node is unique
+    first shared node stops it -> everything after it is reachable from
+    another list through that node, so it's shared too (whatever its own count)
+*/
+impl<T, P: PointerFamily> GenericList<T, P> {
+    //None for an empty list *or* a head some other list also points at
+    pub fn head_mut(&mut self) -> Option<&mut T> {
+        self.head
+            .as_mut()
+            .and_then(|node| P::get_mut(node))
+            .map(|node| &mut node.elem)
+    }
+
+    //&mut to every element up to the first shared node
+    pub fn iter_mut_unique(&mut self) -> IterMutUnique<'_, T, P> {
+        IterMutUnique { next: self.head.as_mut().and_then(|node| P::get_mut(node)) }
+    }
+}
+
+pub struct IterMutUnique<'a, T, P: PointerFamily = RcFamily> {
+    next: Option<&'a mut Node<T, P>>,
+}
+
+impl<'a, T, P: PointerFamily> Iterator for IterMutUnique<'a, T, P> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            self.next = node.next.as_mut().and_then(|next| P::get_mut(next));
+            &mut node.elem
+        })
+    }
+}
+
+/*
+IntoIter: hand out elements by value
+    unique node -> try_unwrap succeeds, move the element out, no clone
+    shared node -> someone else still needs it, clone the element
+        and keep our own pointer to the rest (one refcount bump)
+so T: Clone is required, but only shared elements actually get cloned
+*/
+pub struct IntoIter<T, P: PointerFamily = RcFamily>(GenericList<T, P>);
+
+impl<T: Clone, P: PointerFamily> IntoIterator for GenericList<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
 
-impl<T> Drop for List<T> {
+impl<T: Clone, P: PointerFamily> Iterator for IntoIter<T, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.head.take().map(|node| match P::try_unwrap(node) {
+            Ok(node) => {
+                self.0.head = node.next;
+                node.elem
+            }
+            Err(shared) => {
+                self.0.head = shared.next.clone();
+                shared.elem.clone()
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl<T, P: PointerFamily> Drop for GenericList<T, P> {
     fn drop(&mut self) {
         let mut head = self.head.take();
         while let Some(node) = head {
-            if let Ok(mut node) = Rc::try_unwrap(node) {
+            if let Ok(mut node) = P::try_unwrap(node) {
                 head = node.next.take();
             } else {
                 break;
@@ -119,6 +515,7 @@ Use Arc for thread safety
 
 Simply replace every Rc reference with Arc
     'Rc' -> 'std::sync::Arc'
+    that's what ArcFamily does -> sync::List<T> below
 
 In generally, can't mess up thread-safety in Rust
     Thread-safety modeled with Send and Sync traits
@@ -151,6 +548,12 @@ Rc and Arc both use interior mutability for reference count
 
 */
 
+//same list, Arc pointers -> can be shared between threads
+pub mod sync {
+    pub type List<T> = super::GenericList<T, super::ArcFamily>;
+    pub type Iter<'a, T> = super::Iter<'a, T, super::ArcFamily>;
+}
+
 #[cfg(test)]
 mod test {
     use super::List;
@@ -186,4 +589,418 @@ mod test {
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
     }
+
+    #[test]
+    fn sync_basics() {
+        let list = super::sync::List::new().prepend(1).prepend(2).prepend(3);
+        assert_eq!(list.head(), Some(&3));
+        assert_eq!(list.tail().head(), Some(&2));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        // Iterative drop works through Arc too
+        let mut long = super::sync::List::new();
+        for i in 0..100_000 {
+            long = long.prepend(i);
+        }
+    }
+
+    #[test]
+    fn sync_shared_between_threads() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let mut base = super::sync::List::new();
+        for i in 0..100 {
+            base = base.prepend(i);
+        }
+        let base = Arc::new(base);
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let base = Arc::clone(&base);
+                thread::spawn(move || {
+                    // Every thread builds its own history on top of the shared one
+                    let mine = base.prepend(1000 + t);
+                    assert_eq!(mine.tail().head(), Some(&99));
+                    mine.iter().sum::<i32>()
+                })
+            })
+            .collect();
+
+        for (t, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), 4950 + 1000 + t as i32);
+        }
+    }
+
+    /*
+    prove Send/Sync at runtime without a compile-fail test
+        inherent method only exists when the bound holds
+        otherwise method lookup falls back to the trait's default -> false
+    */
+    struct Probe<T>(std::marker::PhantomData<T>);
+
+    trait Fallback {
+        fn is_send(&self) -> bool { false }
+        fn is_sync(&self) -> bool { false }
+    }
+
+    impl<T> Fallback for Probe<T> {}
+
+    impl<T: Send> Probe<T> {
+        fn is_send(&self) -> bool { true }
+    }
+
+    struct SyncProbe<T>(std::marker::PhantomData<T>);
+
+    impl<T> Fallback for SyncProbe<T> {}
+
+    impl<T: Sync> SyncProbe<T> {
+        fn is_sync(&self) -> bool { true }
+    }
+
+    #[test]
+    fn send_sync() {
+        use std::marker::PhantomData;
+
+        assert!(Probe::<super::sync::List<i32>>(PhantomData).is_send());
+        assert!(SyncProbe::<super::sync::List<i32>>(PhantomData).is_sync());
+        assert!(Probe::<super::sync::Iter<'static, i32>>(PhantomData).is_send());
+
+        assert!(!Probe::<List<i32>>(PhantomData).is_send());
+        assert!(!SyncProbe::<List<i32>>(PhantomData).is_sync());
+
+        // Arc doesn't make a non-thread-safe element thread-safe
+        assert!(!Probe::<super::sync::List<std::cell::Cell<i32>>>(PhantomData).is_send());
+    }
+
+    #[test]
+    fn len() {
+        let list = List::new();
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+
+        let list = list.prepend(1).prepend(2).prepend(3);
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+        assert_eq!(list.tail().len(), 2);
+        assert_eq!(list.tail().tail().tail().len(), 0);
+        assert_eq!(list.tail().tail().tail().tail().len(), 0);
+
+        // Branching off an old version counts from there
+        let branch = list.tail().prepend(10).prepend(11);
+        assert_eq!(branch.len(), 4);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn nth_tail() {
+        let list = List::new().prepend(1).prepend(2).prepend(3);
+
+        assert_eq!(list.nth_tail(0).unwrap().head(), Some(&3));
+        assert_eq!(list.nth_tail(2).unwrap().head(), Some(&1));
+        let end = list.nth_tail(3).unwrap();
+        assert!(end.is_empty());
+        assert!(list.nth_tail(4).is_none());
+        assert!(List::<i32>::new().nth_tail(1).is_none());
+    }
+
+    #[test]
+    fn common_suffix_len() {
+        let shared = List::new().prepend(1).prepend(2);
+        let a = shared.prepend(3).prepend(4).prepend(5);
+        let b = shared.prepend(6);
+
+        assert_eq!(a.common_suffix_len(&b), 2);
+        assert_eq!(b.common_suffix_len(&a), 2);
+        assert_eq!(a.common_suffix_len(&a), 5);
+        assert_eq!(a.common_suffix_len(&shared), 2);
+        assert_eq!(a.common_suffix_len(&a.tail()), 4);
+
+        // Equal values in separate nodes don't count
+        let lookalike = List::new().prepend(1).prepend(2);
+        assert_eq!(shared.common_suffix_len(&lookalike), 0);
+
+        let empty = List::new();
+        assert_eq!(a.common_suffix_len(&empty), 0);
+        assert_eq!(empty.common_suffix_len(&List::new()), 0);
+    }
+
+    fn to_vec<T: Clone>(list: &List<T>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
+
+    //how many lists/nodes point at the first node of list
+    fn head_count<T>(list: &List<T>) -> usize {
+        list.head.as_ref().map_or(0, std::rc::Rc::strong_count)
+    }
+
+    fn from_slice(elems: &[i32]) -> List<i32> {
+        elems.iter().rev().fold(List::new(), |list, &elem| list.prepend(elem))
+    }
+
+    #[test]
+    fn append() {
+        let a = from_slice(&[1, 2]);
+        let b = from_slice(&[3, 4, 5]);
+        assert_eq!(head_count(&b), 1);
+
+        let c = a.append(&b);
+        assert_eq!(to_vec(&c), vec![1, 2, 3, 4, 5]);
+        assert_eq!(c.len(), 5);
+        // c's copy of a ends by pointing at b's first node
+        assert_eq!(head_count(&b), 2);
+        assert_eq!(c.common_suffix_len(&b), 3);
+        // Inputs untouched
+        assert_eq!(to_vec(&a), vec![1, 2]);
+        assert_eq!(to_vec(&b), vec![3, 4, 5]);
+
+        // Appending nothing is the same list
+        let same = a.append(&List::new());
+        assert_eq!(head_count(&a), 2);
+        assert_eq!(same.common_suffix_len(&a), 2);
+        let from_empty = List::new().append(&b);
+        assert_eq!(from_empty.common_suffix_len(&b), 3);
+    }
+
+    #[test]
+    fn reverse() {
+        let list = from_slice(&[1, 2, 3]);
+        let rev = list.reverse();
+        assert_eq!(to_vec(&rev), vec![3, 2, 1]);
+        assert_eq!(to_vec(&list), vec![1, 2, 3]);
+        assert_eq!(rev.len(), 3);
+        assert_eq!(rev.common_suffix_len(&list), 0);
+
+        let single = from_slice(&[7]);
+        let single_rev = single.reverse();
+        assert_eq!(head_count(&single), 2);
+        assert_eq!(to_vec(&single_rev), vec![7]);
+    }
+
+    #[test]
+    fn map() {
+        let list = from_slice(&[1, 2, 3]);
+        let strings = list.map(|x| format!("#{}", x));
+        assert_eq!(to_vec(&strings), vec!["#1", "#2", "#3"]);
+        assert_eq!(strings.len(), 3);
+        assert_eq!(to_vec(&list), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn filter() {
+        let list = from_slice(&[1, 2, 3, 4, 5, 6]);
+
+        // Last dropped element is 3 -> [4, 5, 6] is shared
+        let mut seen = Vec::new();
+        let kept = list.filter(|&x| {
+            seen.push(x);
+            x != 1 && x != 3
+        });
+        assert_eq!(seen, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(to_vec(&kept), vec![2, 4, 5, 6]);
+        assert_eq!(kept.len(), 4);
+        assert_eq!(kept.common_suffix_len(&list), 3);
+        assert_eq!(head_count(&list.nth_tail(3).unwrap()), 3);
+
+        // Nothing dropped -> the whole list is shared
+        let all = list.filter(|_| true);
+        assert_eq!(all.common_suffix_len(&list), 6);
+
+        // Last element dropped -> nothing to share
+        let no_six = list.filter(|&x| x != 6);
+        assert_eq!(to_vec(&no_six), vec![1, 2, 3, 4, 5]);
+        assert_eq!(no_six.common_suffix_len(&list), 0);
+
+        assert!(list.filter(|_| false).is_empty());
+    }
+
+    #[test]
+    fn take_skip() {
+        let list = from_slice(&[1, 2, 3, 4]);
+
+        let front = list.take(2);
+        assert_eq!(to_vec(&front), vec![1, 2]);
+        assert_eq!(front.common_suffix_len(&list), 0);
+        assert_eq!(list.take(10).common_suffix_len(&list), 4);
+        assert!(list.take(0).is_empty());
+
+        let back = list.skip(2);
+        assert_eq!(to_vec(&back), vec![3, 4]);
+        // skip copies nothing: the back half is the original nodes
+        assert_eq!(back.common_suffix_len(&list), 2);
+        assert_eq!(head_count(&back), 2);
+        assert!(list.skip(4).is_empty());
+        assert!(list.skip(10).is_empty());
+    }
+
+    #[test]
+    fn zip_fold() {
+        let nums = from_slice(&[1, 2, 3]);
+        let words = List::new().prepend("b").prepend("a");
+
+        let pairs = nums.zip(&words);
+        assert_eq!(to_vec(&pairs), vec![(1, "a"), (2, "b")]);
+        assert_eq!(pairs.len(), 2);
+
+        assert_eq!(nums.fold(0, |acc, x| acc + x), 6);
+        assert_eq!(nums.fold(String::new(), |acc, x| acc + &x.to_string()), "123");
+        assert_eq!(to_vec(&nums), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn ops_on_long_lists() {
+        let mut list = List::new();
+        for i in 0..100_000 {
+            list = list.prepend(i);
+        }
+        assert_eq!(list.reverse().head(), Some(&0));
+        assert_eq!(list.map(|x| x * 2).len(), 100_000);
+        assert_eq!(list.filter(|x| x % 2 == 0).len(), 50_000);
+        assert_eq!(list.append(&list).len(), 200_000);
+    }
+
+    #[test]
+    fn head_mut() {
+        let mut list = List::new().prepend(1).prepend(2);
+        *list.head_mut().unwrap() = 20;
+        assert_eq!(list.head(), Some(&20));
+
+        // Another list points at the head -> no mutation allowed
+        let other = list.clone();
+        assert_eq!(list.head_mut(), None);
+        drop(other);
+        assert_eq!(list.head_mut(), Some(&mut 20));
+
+        // Only the tail is shared -> head is still ours
+        let tail = list.tail();
+        assert_eq!(list.head_mut(), Some(&mut 20));
+        assert_eq!(tail.head(), Some(&1));
+
+        assert_eq!(List::<i32>::new().head_mut(), None);
+    }
+
+    #[test]
+    fn iter_mut_unique() {
+        let shared = List::new().prepend(1).prepend(2);
+        let mut list = shared.prepend(3).prepend(4);
+
+        // Stops at 2 -> shared with `shared`
+        for elem in list.iter_mut_unique() {
+            *elem *= 10;
+        }
+        assert_eq!(to_vec(&list), vec![40, 30, 2, 1]);
+        assert_eq!(to_vec(&shared), vec![2, 1]);
+
+        // Drop the other owner -> whole list is unique now
+        drop(shared);
+        assert_eq!(list.iter_mut_unique().count(), 4);
+    }
+
+    #[test]
+    fn into_iter_moves_or_clones() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        #[derive(Debug, PartialEq)]
+        struct CountClones(i32, Rc<Cell<usize>>);
+
+        impl Clone for CountClones {
+            fn clone(&self) -> Self {
+                self.1.set(self.1.get() + 1);
+                CountClones(self.0, self.1.clone())
+            }
+        }
+
+        let clones = Rc::new(Cell::new(0));
+        let shared = List::new()
+            .prepend(CountClones(1, clones.clone()))
+            .prepend(CountClones(2, clones.clone()));
+        let list = shared
+            .prepend(CountClones(3, clones.clone()))
+            .prepend(CountClones(4, clones.clone()));
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        let out: Vec<i32> = iter.by_ref().map(|x| x.0).collect();
+        assert_eq!(out, vec![4, 3, 2, 1]);
+        // 4 and 3 were moved out, 2 and 1 had to be cloned
+        assert_eq!(clones.get(), 2);
+        assert_eq!(shared.iter().map(|x| x.0).collect::<Vec<_>>(), vec![2, 1]);
+
+        // Fully unique list -> no clones at all
+        let unique = List::new()
+            .prepend(CountClones(5, clones.clone()))
+            .prepend(CountClones(6, clones.clone()));
+        let out: Vec<i32> = unique.into_iter().map(|x| x.0).collect();
+        assert_eq!(out, vec![6, 5]);
+        assert_eq!(clones.get(), 2);
+    }
+
+    #[test]
+    fn view() {
+        use super::ListRef::{Cons, Nil};
+
+        // Recursion straight on the persistent list, no refcounts touched
+        fn sum(list: &List<i32>) -> i32 {
+            match list.view() {
+                Nil => 0,
+                Cons(head, tail) => head + sum(tail),
+            }
+        }
+
+        fn second(list: &List<i32>) -> Option<&i32> {
+            match list.view() {
+                Cons(_, tail) => match tail.view() {
+                    Cons(x, _) => Some(x),
+                    Nil => None,
+                },
+                Nil => None,
+            }
+        }
+
+        let list = from_slice(&[1, 2, 3, 4]);
+        assert_eq!(sum(&list), 10);
+        assert_eq!(second(&list), Some(&2));
+        assert_eq!(head_count(&list.tail()), 2);
+        assert!(matches!(List::<i32>::new().view(), Nil));
+    }
+
+    #[test]
+    fn split_first() {
+        let list = from_slice(&[1, 2, 3]);
+        let tail_before = head_count(&list.tail());
+
+        let (first, rest) = list.split_first().unwrap();
+        assert_eq!(*first, 1);
+        assert_eq!(rest.head(), Some(&2));
+        assert_eq!(rest.len(), 2);
+        // Borrowed tail -> no extra owner
+        assert_eq!(head_count(rest), tail_before - 1);
+
+        // Walk the whole list by splitting
+        let mut cur = &list;
+        let mut seen = Vec::new();
+        while let Some((head, tail)) = cur.split_first() {
+            seen.push(*head);
+            cur = tail;
+        }
+        assert_eq!(seen, vec![1, 2, 3]);
+        assert!(cur.is_empty());
+    }
+
+    #[test]
+    fn uncons() {
+        let list = from_slice(&[1, 2]);
+        assert_eq!(head_count(&list.tail()) - 1, 1);
+
+        let (head, tail) = list.uncons().unwrap();
+        assert_eq!(*head, 1);
+        assert_eq!(tail.head(), Some(&2));
+        // Owned tail is a second owner of node 2
+        assert_eq!(head_count(&tail), 2);
+
+        let (head, tail) = tail.uncons().unwrap();
+        assert_eq!(*head, 2);
+        assert!(tail.uncons().is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,324 @@
+/*
+unrolled linked list -> each node holds up to N elements inline
+    second::List does one Box::new per push, and one pointer hop per element
+    here one allocation (and one hop) covers N elements
+
+const generics: Stack<T, const N: usize>
+    N is part of the type, like [T; N] -> Stack<i32, 8> and Stack<i32, 16> are different types
+
+only the head node is ever partly full
+    push fills the head until it has N elements, then allocates a new head
+    pop empties the head, then frees it and moves on to the next (full) node
+    so the top of the stack is always head.elems[head.len - 1]
+
+node storage is [MaybeUninit<T>; N]
+    [T; N] would need N real values up front -> nothing to put there yet
+    MaybeUninit<T> is memory for a T that may not be initialized
+        compiler won't drop it or assume it's valid
+    we track how many slots are initialized (len) and only ever touch those
+*/
+
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::slice;
+
+pub struct Stack<T, const N: usize> {
+    head: Link<T, N>,
+}
+
+type Link<T, const N: usize> = Option<Box<Node<T, N>>>;
+
+struct Node<T, const N: usize> {
+    //elems[..len] are initialized, elems[len..] are not
+    elems: [MaybeUninit<T>; N],
+    len: usize,
+    next: Link<T, N>,
+}
+
+impl<T, const N: usize> Node<T, N> {
+    fn new(next: Link<T, N>) -> Box<Self> {
+        Box::new(Node {
+            //array of uninitialized slots -> each slot is a MaybeUninit, no unsafe needed
+            elems: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+            next,
+        })
+    }
+
+    fn as_slice(&self) -> &[T] {
+        //first len slots are initialized, MaybeUninit<T> has the same layout as T
+        unsafe { slice::from_raw_parts(self.elems.as_ptr() as *const T, self.len) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.elems.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+//compiler doesn't drop MaybeUninit contents -> drop the initialized slots ourselves
+impl<T, const N: usize> Drop for Node<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<T, const N: usize> Stack<T, N> {
+    pub fn new() -> Self {
+        //N = 0 would mean nodes that can never hold anything -> refuse at compile time
+        const { assert!(N > 0, "unrolled::Stack needs at least one element per node") };
+        Stack { head: None }
+    }
+
+    pub fn push(&mut self, elem: T) {
+        let full = self.head.as_ref().is_none_or(|node| node.len == N);
+        if full {
+            self.head = Some(Node::new(self.head.take()));
+        }
+        let node = self.head.as_mut().unwrap();
+        node.elems[node.len].write(elem);
+        node.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let node = self.head.as_mut()?;
+        node.len -= 1;
+        //slot is past len now -> nobody else will read or drop it
+        let elem = unsafe { node.elems[node.len].assume_init_read() };
+        if node.len == 0 {
+            //head is empty -> free it, next node (if any) is full
+            let mut empty = self.head.take().unwrap();
+            self.head = empty.next.take();
+        }
+        Some(elem)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.head.as_ref().and_then(|node| node.as_slice().last())
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.head.as_mut().and_then(|node| node.as_mut_slice().last_mut())
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter { elems: [].iter().rev(), next: self.head.as_deref() }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+        IterMut { elems: [].iter_mut().rev(), next: self.head.as_deref_mut() }
+    }
+}
+
+impl<T, const N: usize> Default for Stack<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//same iterative drop as second.rs -> Node's own Drop only handles its elements
+impl<T, const N: usize> Drop for Stack<T, N> {
+    fn drop(&mut self) {
+        let mut cur_link = self.head.take();
+        while let Some(mut boxed_node) = cur_link {
+            cur_link = boxed_node.next.take();
+        }
+    }
+}
+
+pub struct IntoIter<T, const N: usize>(Stack<T, N>);
+
+impl<T, const N: usize> IntoIterator for Stack<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+}
+
+/*
+Iter/IterMut walk one node's slice back to front (top of stack first)
+then hop to the next node -> reuse std's slice iterators for the inner loop
+IterMut splits a &mut Node into its slice and its next link -> disjoint borrows
+*/
+pub struct Iter<'a, T, const N: usize> {
+    elems: std::iter::Rev<slice::Iter<'a, T>>,
+    next: Option<&'a Node<T, N>>,
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(elem) = self.elems.next() {
+                return Some(elem);
+            }
+            let node = self.next.take()?;
+            self.elems = node.as_slice().iter().rev();
+            self.next = node.next.as_deref();
+        }
+    }
+}
+
+pub struct IterMut<'a, T, const N: usize> {
+    elems: std::iter::Rev<slice::IterMut<'a, T>>,
+    next: Option<&'a mut Node<T, N>>,
+}
+
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(elem) = self.elems.next() {
+                return Some(elem);
+            }
+            let node = self.next.take()?;
+            let len = node.len;
+            //borrow elems and next separately -> as_mut_slice would borrow the whole node
+            self.elems = unsafe {
+                slice::from_raw_parts_mut(node.elems.as_mut_ptr() as *mut T, len)
+            }
+            .iter_mut()
+            .rev();
+            self.next = node.next.as_deref_mut();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stack;
+
+    #[test]
+    fn basics() {
+        let mut list: Stack<i32, 2> = Stack::new();
+
+        // Check empty list behaves right
+        assert_eq!(list.pop(), None);
+
+        // Populate list -> spills into a second node
+        list.push(1);
+        list.push(2);
+        list.push(3);
+
+        // Check normal removal
+        assert_eq!(list.pop(), Some(3));
+        assert_eq!(list.pop(), Some(2));
+
+        // Push some more just to make sure nothing's corrupted
+        list.push(4);
+        list.push(5);
+
+        // Check normal removal
+        assert_eq!(list.pop(), Some(5));
+        assert_eq!(list.pop(), Some(4));
+
+        // Check exhaustion
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn peek() {
+        let mut list: Stack<i32, 3> = Stack::new();
+        assert_eq!(list.peek(), None);
+        assert_eq!(list.peek_mut(), None);
+        list.push(1); list.push(2); list.push(3); list.push(4);
+
+        assert_eq!(list.peek(), Some(&4));
+        if let Some(value) = list.peek_mut() {
+            *value = 42
+        }
+        assert_eq!(list.pop(), Some(42));
+        assert_eq!(list.peek(), Some(&3));
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut list: Stack<i32, 2> = Stack::new();
+        list.push(1); list.push(2); list.push(3);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn iter() {
+        let mut list: Stack<i32, 2> = Stack::new();
+        assert_eq!(list.iter().next(), None);
+        list.push(1); list.push(2); list.push(3);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut list: Stack<i32, 2> = Stack::new();
+        list.push(1); list.push(2); list.push(3);
+
+        for elem in list.iter_mut() {
+            *elem *= 10;
+        }
+
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.next(), Some(&mut 30));
+        assert_eq!(iter.next(), Some(&mut 20));
+        assert_eq!(iter.next(), Some(&mut 10));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn one_per_node() {
+        // N = 1 degenerates to second::List
+        let mut list: Stack<String, 1> = Stack::new();
+        list.push("a".to_string());
+        list.push("b".to_string());
+        assert_eq!(list.iter().map(String::as_str).collect::<Vec<_>>(), vec!["b", "a"]);
+        assert_eq!(list.pop().as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn drops_every_element_once() {
+        use std::rc::Rc;
+
+        // Partly full head, full nodes behind it, some elements popped
+        let tracker = Rc::new(());
+        {
+            let mut list: Stack<Rc<()>, 4> = Stack::new();
+            for _ in 0..10 {
+                list.push(tracker.clone());
+            }
+            drop(list.pop());
+            drop(list.pop());
+            assert_eq!(Rc::strong_count(&tracker), 9);
+
+            let mut iter = list.into_iter();
+            drop(iter.next());
+            assert_eq!(Rc::strong_count(&tracker), 8);
+        }
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+
+    #[test]
+    fn long_list_drop() {
+        let mut list: Stack<u64, 4> = Stack::new();
+        for i in 0..400_000 {
+            list.push(i);
+        }
+    }
+}